@@ -0,0 +1,161 @@
+use color_eyre::eyre::{Result, WrapErr};
+use common::{input::YEAR, Answer};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// The answers known for a particular input.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct Known {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    part1: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    part2: Option<String>,
+}
+
+impl Known {
+    fn part(&self, part: u8) -> Option<&String> {
+        match part {
+            1 => self.part1.as_ref(),
+            2 => self.part2.as_ref(),
+            _ => None,
+        }
+    }
+
+    fn part_mut(&mut self, part: u8) -> &mut Option<String> {
+        match part {
+            1 => &mut self.part1,
+            2 => &mut self.part2,
+            _ => panic!("puzzles have only two parts; requested part {part}"),
+        }
+    }
+}
+
+/// Year -> day -> input hash -> known answers
+type Entries = BTreeMap<String, BTreeMap<String, BTreeMap<String, Known>>>;
+
+/// Hash the contents of an input file.
+///
+/// Everybody gets different inputs, so answers are keyed by the input which produced them.
+pub fn input_hash(input: &Path) -> Result<String> {
+    let data =
+        std::fs::read(input).wrap_err_with(|| format!("reading {} to hash it", input.display()))?;
+    Ok(format!("{:x}", Sha256::digest(data)))
+}
+
+fn day_key(day: u8) -> String {
+    format!("day{day:02}")
+}
+
+/// The outcome of comparing a computed answer with the store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    Match,
+    Mismatch { expected: String },
+    Unknown,
+}
+
+/// A TOML file recording the correct answers for each input.
+#[derive(Debug)]
+pub struct AnswerStore {
+    path: PathBuf,
+    entries: Entries,
+}
+
+impl AnswerStore {
+    /// Load the store at `path`. A missing file is an empty store.
+    pub fn load(path: &Path) -> Result<Self> {
+        let entries = if path.exists() {
+            let data = std::fs::read_to_string(path)
+                .wrap_err_with(|| format!("reading answers from {}", path.display()))?;
+            toml::from_str(&data)
+                .wrap_err_with(|| format!("parsing answers from {}", path.display()))?
+        } else {
+            Entries::default()
+        };
+        Ok(Self {
+            path: path.to_owned(),
+            entries,
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let data = toml::to_string_pretty(&self.entries)?;
+        std::fs::write(&self.path, data)
+            .wrap_err_with(|| format!("writing answers to {}", self.path.display()))
+    }
+
+    fn get(&self, day: u8, input_hash: &str, part: u8) -> Option<&String> {
+        self.entries
+            .get(&YEAR.to_string())?
+            .get(&day_key(day))?
+            .get(input_hash)?
+            .part(part)
+    }
+
+    pub fn check(&self, day: u8, input_hash: &str, part: u8, answer: &Answer) -> Check {
+        match self.get(day, input_hash, part) {
+            None => Check::Unknown,
+            Some(expected) if *expected == answer.to_string() => Check::Match,
+            Some(expected) => Check::Mismatch {
+                expected: expected.clone(),
+            },
+        }
+    }
+
+    pub fn record(&mut self, day: u8, input_hash: &str, part: u8, answer: &Answer) {
+        *self
+            .entries
+            .entry(YEAR.to_string())
+            .or_default()
+            .entry(day_key(day))
+            .or_default()
+            .entry(input_hash.to_owned())
+            .or_default()
+            .part_mut(part) = Some(answer.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_then_check() {
+        let mut store = AnswerStore {
+            path: PathBuf::new(),
+            entries: Entries::default(),
+        };
+        let answer = Answer::from(42_u32);
+        assert_eq!(store.check(1, "abc", 1, &answer), Check::Unknown);
+
+        store.record(1, "abc", 1, &answer);
+        assert_eq!(store.check(1, "abc", 1, &answer), Check::Match);
+        assert_eq!(store.check(1, "abc", 2, &answer), Check::Unknown);
+        assert_eq!(store.check(1, "def", 1, &answer), Check::Unknown);
+        assert_eq!(
+            store.check(1, "abc", 1, &Answer::from(43_u32)),
+            Check::Mismatch {
+                expected: "42".into()
+            }
+        );
+    }
+
+    #[test]
+    fn round_trip_toml() {
+        let mut store = AnswerStore {
+            path: PathBuf::new(),
+            entries: Entries::default(),
+        };
+        store.record(10, "abc", 2, &Answer::Grid("#.\n.#".into()));
+        let data = toml::to_string_pretty(&store.entries).unwrap();
+        let entries: Entries = toml::from_str(&data).unwrap();
+        assert_eq!(
+            entries["2022"]["day10"]["abc"].part2.as_deref(),
+            Some("#.\n.#")
+        );
+    }
+}
@@ -0,0 +1,506 @@
+mod answers;
+mod registry;
+mod serve;
+mod summary;
+mod watch;
+
+use answers::{AnswerStore, Check};
+use color_eyre::eyre::{bail, eyre, Result};
+use common::{bench::Bench, memory::Usage, Answer, Format, InputArgs, LogArgs, Report};
+use registry::Day;
+use std::{
+    collections::HashMap,
+    fs::OpenOptions,
+    io::Write,
+    num::NonZeroUsize,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    str::FromStr,
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+use structopt::StructOpt;
+use watch::Watched;
+
+#[cfg(feature = "count-allocations")]
+#[global_allocator]
+static ALLOCATOR: common::memory::CountingAllocator = common::memory::CountingAllocator;
+
+/// Run `f`, measuring its heap usage if allocations are being counted.
+fn measure_memory<T>(f: impl FnOnce() -> T) -> (T, Option<Usage>) {
+    #[cfg(feature = "count-allocations")]
+    {
+        let (value, usage) = common::memory::measure(f);
+        (value, Some(usage))
+    }
+    #[cfg(not(feature = "count-allocations"))]
+    {
+        (f(), None)
+    }
+}
+
+/// Which days to run.
+#[derive(Debug, Clone, Copy)]
+enum Selection {
+    All,
+    Day(u8),
+}
+
+impl FromStr for Selection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("all") {
+            return Ok(Selection::All);
+        }
+        s.parse()
+            .map(Selection::Day)
+            .map_err(|_| format!("expected a day number or \"all\"; got {s:?}"))
+    }
+}
+
+#[derive(StructOpt, Debug)]
+struct RunArgs {
+    /// day to run, or "all"
+    selection: Selection,
+
+    #[structopt(flatten)]
+    input: InputArgs,
+
+    #[structopt(flatten)]
+    log: LogArgs,
+
+    /// skip part 1
+    #[structopt(long)]
+    no_part1: bool,
+
+    /// run part 2
+    #[structopt(long)]
+    part2: bool,
+
+    /// output format: "text" or "json"
+    #[structopt(long, default_value)]
+    format: Format,
+
+    /// compare answers with the answers file, failing on any mismatch
+    #[structopt(long, conflicts_with = "record")]
+    check: bool,
+
+    /// record answers into the answers file
+    #[structopt(long)]
+    record: bool,
+
+    /// file in which known answers are stored
+    #[structopt(long, parse(from_os_str), default_value = "answers.toml")]
+    answers: PathBuf,
+
+    /// benchmark each part over N runs instead of printing answers
+    #[structopt(long, value_name = "N", conflicts_with_all = &["check", "record"])]
+    bench: Option<usize>,
+
+    /// append benchmark results to this CSV file
+    #[structopt(long, parse(from_os_str), requires = "bench")]
+    csv: Option<PathBuf>,
+
+    /// re-run the selected parts whenever the input changes; only valid when running a single day
+    #[structopt(long, conflicts_with_all = &["bench", "check", "record"])]
+    watch: bool,
+
+    /// another input to solve and watch alongside the first, such as a hand-written example
+    #[structopt(long, parse(from_os_str), requires = "watch")]
+    alt_input: Option<PathBuf>,
+
+    /// parameters for the day, as accepted by its own binary; only valid when running a single day
+    #[structopt(last = true)]
+    params: Vec<String>,
+}
+
+impl RunArgs {
+    fn days(&self) -> Result<Vec<&'static Day>> {
+        match self.selection {
+            Selection::All => {
+                if self.input.is_single_day() {
+                    bail!("--input can only be used when running a single day");
+                }
+                if !self.params.is_empty() {
+                    bail!("day parameters can only be used when running a single day");
+                }
+                Ok(registry::DAYS.iter().collect())
+            }
+            Selection::Day(day) => registry::find(day)
+                .map(|day| vec![day])
+                .ok_or_else(|| eyre!("day {day} is not registered")),
+        }
+    }
+
+    /// Resolve the input file for a day.
+    fn input(&self, day: &Day) -> Result<PathBuf> {
+        self.input.source(day.example).locate(day.day)
+    }
+
+    fn parts(&self) -> impl Iterator<Item = u8> {
+        let part1 = (!self.no_part1).then_some(1);
+        let part2 = self.part2.then_some(2);
+        part1.into_iter().chain(part2)
+    }
+}
+
+#[derive(StructOpt, Debug)]
+struct SummaryArgs {
+    #[structopt(flatten)]
+    input: InputArgs,
+
+    #[structopt(flatten)]
+    log: LogArgs,
+
+    /// number of parts to run at once [default: available parallelism]
+    #[structopt(long, short)]
+    jobs: Option<NonZeroUsize>,
+
+    /// output format: "text" or "json"
+    #[structopt(long, default_value)]
+    format: Format,
+}
+
+#[derive(StructOpt, Debug)]
+struct GenArgs {
+    /// day to generate an input for
+    day: u8,
+
+    /// seed for the generator; if omitted, a random seed is chosen and printed to stderr
+    #[structopt(long)]
+    seed: Option<u64>,
+
+    /// how large an input to generate; what this counts varies by day
+    #[structopt(long, default_value = "100")]
+    size: usize,
+
+    /// write the input to this file instead of stdout
+    #[structopt(long, short, parse(from_os_str))]
+    output: Option<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+struct ServeArgs {
+    #[structopt(flatten)]
+    log: LogArgs,
+
+    /// address to listen on
+    #[structopt(long, default_value = "127.0.0.1:2022")]
+    addr: String,
+
+    /// seconds to wait for a part to solve before giving up on it
+    #[structopt(long, value_name = "SECONDS", default_value = "30")]
+    timeout: f64,
+
+    /// how many parts may solve at once, including those still running after timing out; defaults
+    /// to the number of CPUs
+    #[structopt(long)]
+    workers: Option<NonZeroUsize>,
+}
+
+#[derive(StructOpt, Debug)]
+struct ReplArgs {
+    /// day whose simulation to step through
+    day: u8,
+
+    #[structopt(flatten)]
+    input: InputArgs,
+
+    #[structopt(flatten)]
+    log: LogArgs,
+
+    /// step through part 2 instead of part 1
+    #[structopt(long)]
+    part2: bool,
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(about = "Advent of Code 2022 runner")]
+enum Command {
+    /// run one or all days
+    Run(RunArgs),
+    /// run both parts of every day in parallel, and summarize the results in a table
+    Summary(SummaryArgs),
+    /// generate a random, valid input for a day
+    Gen(GenArgs),
+    /// solve inputs POSTed to /2022/{day}/{part} over HTTP, responding with JSON
+    Serve(ServeArgs),
+    /// step through a simulation interactively: step, run until a condition, print, and rewind
+    Repl(ReplArgs),
+}
+
+fn print_text(report: &Report) {
+    match &report.answer {
+        Answer::Grid(grid) => println!("day {:02} part {}:\n{grid}", report.day, report.part),
+        answer => println!("day {:02} part {}: {answer}", report.day, report.part),
+    }
+    for line in &report.extra {
+        println!("  {line}");
+    }
+    if let Some(memory) = report.memory {
+        println!("  memory: {memory}");
+    }
+}
+
+/// Compare a report against the known answers, returning `true` if it is known to be wrong.
+fn check(store: &AnswerStore, input_hash: &str, report: &Report) -> bool {
+    let Report { day, part, .. } = *report;
+    match store.check(day, input_hash, part, &report.answer) {
+        Check::Match => {
+            eprintln!("day {day:02} part {part}: matches known answer");
+            false
+        }
+        Check::Unknown => {
+            eprintln!("day {day:02} part {part}: no known answer");
+            false
+        }
+        Check::Mismatch { expected } => {
+            eprintln!(
+                "day {day:02} part {part}: MISMATCH: expected {expected}; got {}",
+                report.answer
+            );
+            true
+        }
+    }
+}
+
+fn append_csv(path: &Path, benches: &[Bench]) -> Result<()> {
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if is_new {
+        writeln!(file, "{}", Bench::CSV_HEADER)?;
+    }
+    for bench in benches {
+        for row in bench.csv_rows(timestamp) {
+            writeln!(file, "{row}")?;
+        }
+    }
+    Ok(())
+}
+
+fn bench(args: &RunArgs, runs: usize) -> Result<()> {
+    if runs == 0 {
+        bail!("--bench requires at least 1 run");
+    }
+
+    let mut benches = Vec::new();
+    for day in args.days()? {
+        let input = args.input(day)?;
+        for part in args.parts() {
+            let bench = Bench::run(day.day, part, runs, || {
+                (day.bench)(&input, part, &args.params)
+            })?;
+            println!("{bench}");
+            benches.push(bench);
+        }
+    }
+
+    if let Some(csv) = &args.csv {
+        append_csv(csv, &benches)?;
+    }
+    Ok(())
+}
+
+/// Solve the selected parts of `day` whenever one of its inputs changes, until interrupted.
+fn watch(args: &RunArgs) -> Result<()> {
+    let day = match args.days()?[..] {
+        [day] => day,
+        _ => bail!("--watch can only be used when running a single day"),
+    };
+    let mut watched = std::iter::once(args.input(day)?)
+        .chain(args.alt_input.clone())
+        .map(Watched::new)
+        .collect::<Vec<_>>();
+    let mut previous = HashMap::new();
+    let mut changed = (0..watched.len()).collect::<Vec<_>>();
+
+    loop {
+        for &idx in &changed {
+            let input = &watched[idx].path;
+            eprintln!("==> {} <==", input.display());
+            for part in args.parts() {
+                let start = Instant::now();
+                // keep watching through failures; the panic hook has already reported any panic
+                let solution = match panic::catch_unwind(AssertUnwindSafe(|| {
+                    (day.solve)(input, part, &args.params)
+                })) {
+                    Ok(Ok(solution)) => solution,
+                    Ok(Err(err)) => {
+                        eprintln!("day {:02} part {part}: {err:?}", day.day);
+                        continue;
+                    }
+                    Err(_) => continue,
+                };
+                let report = Report::new(day.day, part, solution, start.elapsed());
+                match args.format {
+                    Format::Text => {
+                        print_text(&report);
+                        let change = watch::diff(previous.get(&(idx, part)), &report.answer);
+                        for line in change.iter().flat_map(|change| change.lines()) {
+                            println!("  {line}");
+                        }
+                    }
+                    Format::Json => println!("{}", report.to_json()),
+                }
+                previous.insert((idx, part), report.answer);
+            }
+        }
+
+        changed.clear();
+        while changed.is_empty() {
+            thread::sleep(watch::POLL_INTERVAL);
+            changed = watched
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(idx, watched)| watched.changed().then_some(idx))
+                .collect();
+        }
+    }
+}
+
+fn run(args: &RunArgs) -> Result<()> {
+    if let Some(runs) = args.bench {
+        return bench(args, runs);
+    }
+    if args.watch {
+        return watch(args);
+    }
+
+    let mut store = (args.check || args.record)
+        .then(|| AnswerStore::load(&args.answers))
+        .transpose()?;
+    let mut mismatches = 0;
+
+    for day in args.days()? {
+        let input = args.input(day)?;
+        let input_hash = store
+            .is_some()
+            .then(|| answers::input_hash(&input))
+            .transpose()?;
+
+        for part in args.parts() {
+            let start = Instant::now();
+            let (solution, memory) = measure_memory(|| (day.solve)(&input, part, &args.params));
+            let report = Report::new(day.day, part, solution?, start.elapsed()).with_memory(memory);
+            match args.format {
+                Format::Text => print_text(&report),
+                Format::Json => println!("{}", report.to_json()),
+            }
+
+            if let Some((store, input_hash)) = store.as_mut().zip(input_hash.as_deref()) {
+                if args.record {
+                    store.record(day.day, input_hash, part, &report.answer);
+                } else if check(store, input_hash, &report) {
+                    mismatches += 1;
+                }
+            }
+        }
+    }
+
+    if let Some(store) = store.as_ref().filter(|_| args.record) {
+        store.save()?;
+    }
+    if mismatches > 0 {
+        bail!(
+            "{mismatches} answer(s) did not match {}",
+            args.answers.display()
+        );
+    }
+    Ok(())
+}
+
+fn summary(args: &SummaryArgs) -> Result<()> {
+    if args.input.is_single_day() {
+        bail!("--input can only be used when running a single day");
+    }
+
+    let days = registry::DAYS.iter().collect::<Vec<_>>();
+    let jobs = args.jobs.unwrap_or_else(summary::default_jobs);
+    let start = Instant::now();
+    let rows = summary::run(&days, jobs, |day| {
+        args.input.source(day.example).locate(day.day)
+    });
+    let elapsed = start.elapsed();
+
+    match args.format {
+        Format::Text => {
+            println!("{}", summary::Table(&rows));
+            println!("finished in {elapsed:.3?}");
+        }
+        Format::Json => {
+            for row in &rows {
+                println!("{}", row.to_json());
+            }
+        }
+    }
+
+    let failed = rows.iter().filter(|row| !row.outcome.is_ok()).count();
+    if failed > 0 {
+        bail!("{failed} part(s) did not solve");
+    }
+    Ok(())
+}
+
+fn repl(args: &ReplArgs) -> Result<()> {
+    args.log.init()?;
+    let day =
+        registry::find(args.day).ok_or_else(|| eyre!("day {} is not registered", args.day))?;
+    let Some(repl) = day.repl else {
+        let interactive = registry::DAYS
+            .iter()
+            .filter(|day| day.repl.is_some())
+            .map(|day| day.day.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        bail!(
+            "day {} has no simulation to step through; try one of {interactive}",
+            day.day
+        );
+    };
+    let input = args.input.source(day.example).locate(day.day)?;
+    repl(&input, if args.part2 { 2 } else { 1 })
+}
+
+fn generate(args: &GenArgs) -> Result<()> {
+    let day =
+        registry::find(args.day).ok_or_else(|| eyre!("day {} is not registered", args.day))?;
+    let seed = args.seed.unwrap_or_else(|| {
+        let seed = rand::random();
+        eprintln!("seed: {seed}");
+        seed
+    });
+
+    let input = (day.generate)(seed, args.size);
+    match &args.output {
+        Some(path) => std::fs::write(path, input)?,
+        None => print!("{input}"),
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    match Command::from_args() {
+        Command::Run(args) => {
+            args.log.init()?;
+            run(&args)
+        }
+        Command::Summary(args) => {
+            args.log.init()?;
+            summary(&args)
+        }
+        Command::Gen(args) => generate(&args),
+        Command::Serve(args) => {
+            args.log.init()?;
+            let timeout = Duration::try_from_secs_f64(args.timeout)
+                .map_err(|err| eyre!("invalid --timeout: {err}"))?;
+            let workers = args.workers.unwrap_or_else(summary::default_jobs);
+            serve::serve(&args.addr, workers, timeout)
+        }
+        Command::Repl(args) => repl(&args),
+    }
+}
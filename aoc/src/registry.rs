@@ -0,0 +1,156 @@
+use color_eyre::eyre::Result;
+use common::{
+    bench::Timing, puzzle::generate_seeded, repl::Interactive, Annotate, Puzzle, Solution,
+};
+use std::{io, path::Path};
+
+/// Solve a part from an input file, given any parameters for the day.
+pub type SolveFn = fn(&Path, u8, &[String]) -> Result<Solution>;
+/// Solve a part from an input held in memory, given any parameters for the day.
+pub type SolveStrFn = fn(&str, u8, &[String]) -> Result<Solution>;
+pub type BenchFn = fn(&Path, u8, &[String]) -> Result<Timing>;
+/// Generate an input from a seed and a size.
+pub type GenerateFn = fn(u64, usize) -> String;
+/// Step through a part's simulation interactively on stdin and stdout.
+pub type ReplFn = fn(&Path, u8) -> Result<()>;
+
+/// A day which the runner knows how to solve.
+pub struct Day {
+    pub day: u8,
+    /// The worked example from the puzzle description.
+    pub example: &'static str,
+    pub solve: SolveFn,
+    pub solve_str: SolveStrFn,
+    /// Time the parse and solve phases of a part separately.
+    pub bench: BenchFn,
+    pub generate: GenerateFn,
+    /// Present for days whose solutions are simulations which can be stepped through.
+    pub repl: Option<ReplFn>,
+}
+
+impl Day {
+    pub const fn of<P: Puzzle>() -> Self {
+        Self {
+            day: P::DAY,
+            example: P::EXAMPLE,
+            solve: solve::<P>,
+            solve_str: solve_str::<P>,
+            bench: bench::<P>,
+            generate: generate_seeded::<P>,
+            repl: None,
+        }
+    }
+
+    pub const fn interactive<P: Interactive>() -> Self {
+        Self {
+            repl: Some(repl::<P>),
+            ..Self::of::<P>()
+        }
+    }
+}
+
+/// Parse parameters passed through to a day, as though they were given to its own binary.
+fn params<P: Puzzle>(args: &[String]) -> Result<P::Params> {
+    Ok(P::parse_params(args.iter().map(String::as_str))?)
+}
+
+fn solve<P: Puzzle>(input: &Path, part: u8, args: &[String]) -> Result<Solution> {
+    let params = params::<P>(args)?;
+    P::solve_file(input, part, &params).annotate()
+}
+
+fn solve_str<P: Puzzle>(input: &str, part: u8, args: &[String]) -> Result<Solution> {
+    let params = params::<P>(args)?;
+    let parsed = P::parse(input).annotate()?;
+    P::solve(&parsed, part, &params).annotate()
+}
+
+fn bench<P: Puzzle>(input: &Path, part: u8, args: &[String]) -> Result<Timing> {
+    let params = params::<P>(args)?;
+    common::bench::time(input, P::parse_file, |parsed| {
+        P::solve(parsed, part, &params)
+    })
+}
+
+fn repl<P: Interactive>(input: &Path, part: u8) -> Result<()> {
+    let parsed = P::parse_file(input).annotate()?;
+    let simulation = P::simulate(&parsed, part);
+    common::repl::run(simulation, io::stdin().lock(), io::stdout().lock())?;
+    Ok(())
+}
+
+/// Every day the runner knows about, in order.
+///
+/// Adding a day means adding its crate as a dependency and adding a line here.
+pub const DAYS: &[Day] = &[
+    Day::of::<day01::Day01>(),
+    Day::of::<day02::Day02>(),
+    Day::of::<day03::Day03>(),
+    Day::of::<day04::Day04>(),
+    Day::interactive::<day05::Day05>(),
+    Day::of::<day06::Day06>(),
+    Day::of::<day07::Day07>(),
+    Day::of::<day08::Day08>(),
+    Day::interactive::<day09::Day09>(),
+    Day::interactive::<day10::Day10>(),
+    Day::interactive::<day11::Day11>(),
+    Day::of::<day12::Day12>(),
+    Day::of::<day13::Day13>(),
+    Day::interactive::<day14::Day14>(),
+    Day::of::<day15::Day15>(),
+    Day::of::<day16::Day16>(),
+];
+
+pub fn find(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|registered| registered.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{input::Example, InputSource};
+
+    #[test]
+    fn days_are_in_order() {
+        for (idx, day) in DAYS.iter().enumerate() {
+            assert_eq!(day.day as usize, idx + 1);
+        }
+    }
+
+    #[test]
+    fn every_example_solves_part1() {
+        // day 16 is not yet implemented
+        for day in DAYS.iter().filter(|day| day.day != 16) {
+            let input = Example(day.example).locate(day.day).unwrap();
+            (day.solve)(&input, 1, &[]).unwrap();
+        }
+    }
+
+    #[test]
+    fn generated_inputs_are_reproducible() {
+        for day in DAYS {
+            assert_eq!((day.generate)(7, 20), (day.generate)(7, 20));
+        }
+    }
+
+    #[test]
+    fn generated_inputs_solve() {
+        let dir = std::env::temp_dir().join("aoc-2022-generated");
+        std::fs::create_dir_all(&dir).unwrap();
+        for day in DAYS {
+            for seed in 0..3 {
+                let input = dir.join(format!("day{:02}-{seed}.txt", day.day));
+                std::fs::write(&input, (day.generate)(seed, 20)).unwrap();
+                for part in [1, 2] {
+                    // day 15's part 2 scans four million rows; day 16 is not yet implemented
+                    if (part == 2 && day.day == 15) || day.day == 16 {
+                        continue;
+                    }
+                    if let Err(err) = (day.solve)(&input, part, &[]) {
+                        panic!("day {} part {part} seed {seed}: {err:?}", day.day);
+                    }
+                }
+            }
+        }
+    }
+}
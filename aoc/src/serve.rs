@@ -0,0 +1,389 @@
+//! Solve inputs submitted over HTTP.
+//!
+//! `POST /2022/{day}/{part}` with the input as the request body. The response is the JSON of a
+//! summary [`Row`]: the day, part, status, answer or error, and elapsed seconds.
+//!
+//! Parts solve on a bounded set of [`Workers`]. A part which times out can't be stopped, so it
+//! keeps its worker until it finishes; while every worker is busy, requests get a 503.
+
+use crate::{
+    registry::{self, Day},
+    summary::{attempt, Outcome, Row},
+};
+use color_eyre::eyre::{eyre, Result};
+use common::input::YEAR;
+use std::{
+    io::Read,
+    num::NonZeroUsize,
+    panic,
+    sync::{mpsc, Arc, Condvar, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Inputs larger than this are refused; the largest real input is a few tens of KiB.
+pub const MAX_INPUT_BYTES: u64 = 256 << 10;
+
+/// The stack each solve runs on. A stack overflow aborts the whole process, so this is generous;
+/// parsers which recurse also limit their depth.
+pub const SOLVE_STACK_BYTES: usize = 64 << 20;
+
+/// A response which has yet to be sent.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Reply {
+    pub status: u16,
+    /// A JSON document.
+    pub body: String,
+}
+
+impl Reply {
+    fn row(status: u16, row: &Row) -> Self {
+        Self {
+            status,
+            body: row.to_json(),
+        }
+    }
+
+    fn error(status: u16, error: impl ToString) -> Self {
+        Self {
+            status,
+            body: serde_json::json!({ "error": error.to_string() }).to_string(),
+        }
+    }
+}
+
+/// Find the day and part addressed by a path like `/2022/1/2`.
+fn route(path: &str) -> Option<(&'static Day, u8)> {
+    let path = path.split('?').next().unwrap_or_default();
+    let mut segments = path.trim_start_matches('/').split('/');
+    let (Some(year), Some(day), Some(part), None) = (
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+    ) else {
+        return None;
+    };
+    if year.parse::<u32>().ok()? != YEAR {
+        return None;
+    }
+    let part = part.parse().ok().filter(|part| matches!(part, 1 | 2))?;
+    let day = registry::find(day.parse().ok()?)?;
+    Some((day, part))
+}
+
+/// Limits how many parts solve at once, counting those which timed out but are still running.
+#[derive(Debug)]
+pub struct Workers {
+    limit: usize,
+    busy: Mutex<usize>,
+    idle: Condvar,
+}
+
+/// A claim on one of the [`Workers`], released when dropped.
+struct Slot(Arc<Workers>);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        let mut busy = self.0.busy.lock().unwrap_or_else(|err| err.into_inner());
+        *busy -= 1;
+        self.0.idle.notify_all();
+    }
+}
+
+impl Workers {
+    pub fn new(limit: usize) -> Arc<Self> {
+        Arc::new(Self {
+            limit,
+            busy: Mutex::new(0),
+            idle: Condvar::new(),
+        })
+    }
+
+    /// Claim a worker, if one is free.
+    fn claim(self: &Arc<Self>) -> Option<Slot> {
+        let mut busy = self.busy.lock().unwrap_or_else(|err| err.into_inner());
+        if *busy >= self.limit {
+            return None;
+        }
+        *busy += 1;
+        Some(Slot(Arc::clone(self)))
+    }
+
+    /// Block until every worker is free.
+    #[cfg(test)]
+    fn wait_idle(&self) {
+        let mut busy = self.busy.lock().unwrap_or_else(|err| err.into_inner());
+        while *busy > 0 {
+            busy = self.idle.wait(busy).unwrap_or_else(|err| err.into_inner());
+        }
+    }
+}
+
+/// What became of an attempt to solve a part.
+#[derive(Debug)]
+enum Attempt {
+    Finished(Outcome),
+    TimedOut,
+    /// Every worker was busy, so the part never started.
+    Busy,
+}
+
+/// Run `work` on a free worker, giving up on it after `timeout`.
+///
+/// A part which times out can't be stopped, so its worker keeps running in the background until it
+/// finishes, and its result is discarded.
+fn solve(
+    workers: &Arc<Workers>,
+    timeout: Duration,
+    work: impl 'static + Send + FnOnce() -> Outcome,
+) -> Attempt {
+    let Some(slot) = workers.claim() else {
+        return Attempt::Busy;
+    };
+    let (sender, receiver) = mpsc::channel();
+    let spawned = thread::Builder::new()
+        .name("solve".into())
+        .stack_size(SOLVE_STACK_BYTES)
+        .spawn(move || {
+            let outcome = work();
+            // if the request already timed out, nobody is listening
+            let _ = sender.send(outcome);
+            drop(slot);
+        });
+    if let Err(err) = spawned {
+        return Attempt::Finished(Outcome::Failed {
+            error: format!("starting a worker: {err}"),
+        });
+    }
+    match receiver.recv_timeout(timeout) {
+        Ok(outcome) => Attempt::Finished(outcome),
+        Err(_) => Attempt::TimedOut,
+    }
+}
+
+/// Answers requests, solving parts on a bounded set of workers.
+#[derive(Debug, Clone)]
+pub struct Service {
+    workers: Arc<Workers>,
+    timeout: Duration,
+}
+
+impl Service {
+    /// Solve at most `workers` parts at once, giving up on each after `timeout`.
+    pub fn new(workers: usize, timeout: Duration) -> Self {
+        Self {
+            workers: Workers::new(workers),
+            timeout,
+        }
+    }
+
+    /// Decide how to answer a request.
+    ///
+    /// `body` is only read once the request is known to address a part.
+    pub fn respond(
+        &self,
+        method: &Method,
+        path: &str,
+        body: impl FnOnce() -> Result<String>,
+    ) -> Reply {
+        let Some((day, part)) = route(path) else {
+            return Reply::error(
+                404,
+                format!("no such part; expected POST /{YEAR}/{{day}}/{{part}}"),
+            );
+        };
+        if *method != Method::Post {
+            return Reply::error(405, "inputs must be POSTed");
+        }
+        let input = match body() {
+            Ok(input) => input,
+            Err(err) => return Reply::error(400, format!("{err:#}")),
+        };
+
+        let start = Instant::now();
+        let timeout = self.timeout;
+        let attempt = solve(&self.workers, timeout, move || {
+            attempt(|| (day.solve_str)(&input, part, &[]))
+        });
+        let (status, outcome) = match attempt {
+            Attempt::Finished(outcome @ Outcome::Solved { .. }) => (200, outcome),
+            Attempt::Finished(outcome @ Outcome::Failed { .. }) => (422, outcome),
+            Attempt::Finished(outcome @ Outcome::Panicked { .. }) => (500, outcome),
+            Attempt::TimedOut => (
+                504,
+                Outcome::Failed {
+                    error: format!("timed out after {timeout:.3?}"),
+                },
+            ),
+            Attempt::Busy => {
+                return Reply::error(
+                    503,
+                    format!(
+                        "no worker is free (at most {} parts solve at once); try again later",
+                        self.workers.limit
+                    ),
+                )
+            }
+        };
+        let row = Row {
+            day: day.day,
+            part,
+            outcome,
+            elapsed: start.elapsed(),
+        };
+        Reply::row(status, &row)
+    }
+
+    fn handle(&self, mut request: Request) {
+        let method = request.method().clone();
+        let path = request.url().to_owned();
+        let reply = self.respond(&method, &path, || read_body(&mut request));
+        tracing::info!(%method, path, status = reply.status, "handled request");
+
+        let content_type = Header::from_bytes("Content-Type", "application/json")
+            .expect("content type header is valid");
+        let response = Response::from_string(reply.body)
+            .with_status_code(reply.status)
+            .with_header(content_type);
+        if let Err(err) = request.respond(response) {
+            tracing::warn!(%err, "failed to send response");
+        }
+    }
+}
+
+/// Read a request body as UTF-8, refusing anything larger than [`MAX_INPUT_BYTES`].
+fn read_body(request: &mut Request) -> Result<String> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_INPUT_BYTES + 1)
+        .read_to_string(&mut body)
+        .map_err(|err| eyre!("reading input: {err}"))?;
+    if body.len() as u64 > MAX_INPUT_BYTES {
+        return Err(eyre!("input is larger than {MAX_INPUT_BYTES} bytes"));
+    }
+    Ok(body)
+}
+
+/// Serve requests on `addr` until the process is stopped, solving at most `workers` parts at once.
+///
+/// Twice as many threads accept requests, so that requests arriving while every worker is busy are
+/// turned away promptly.
+pub fn serve(addr: &str, workers: NonZeroUsize, timeout: Duration) -> Result<()> {
+    let server = Server::http(addr).map_err(|err| eyre!("listening on {addr}: {err}"))?;
+    let server = Arc::new(server);
+    eprintln!("listening on http://{}", server.server_addr());
+    // panics are reported in the response; the server's log only needs a line for each
+    panic::set_hook(Box::new(|info| tracing::error!(%info, "a part panicked")));
+
+    let service = Service::new(workers.get(), timeout);
+    let handlers = (0..workers.get() * 2)
+        .map(|_| {
+            let server = Arc::clone(&server);
+            let service = service.clone();
+            thread::spawn(move || loop {
+                match server.recv() {
+                    Ok(request) => service.handle(request),
+                    Err(err) => tracing::warn!(%err, "failed to receive request"),
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for handler in handlers {
+        handler
+            .join()
+            .map_err(|_| eyre!("a request handler panicked"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    fn post(path: &str, input: &str) -> (u16, Value) {
+        let service = Service::new(1, TIMEOUT);
+        let reply = service.respond(&Method::Post, path, || Ok(input.to_owned()));
+        (reply.status, serde_json::from_str(&reply.body).unwrap())
+    }
+
+    #[test]
+    fn solves_posted_input() {
+        let (status, body) = post("/2022/1/2", day01::EXAMPLE);
+        assert_eq!(status, 200);
+        assert_eq!(body["day"], 1);
+        assert_eq!(body["part"], 2);
+        assert_eq!(body["status"], "solved");
+        assert_eq!(body["answer"], 45000);
+        assert!(body["elapsed"].is_number());
+    }
+
+    #[test]
+    fn routing_errors() {
+        assert_eq!(post("/2022/26/1", "").0, 404);
+        assert_eq!(post("/2022/1/3", "").0, 404);
+        assert_eq!(post("/2021/1/1", "").0, 404);
+        assert_eq!(post("/2022/1", "").0, 404);
+        let service = Service::new(1, TIMEOUT);
+        let reply = service.respond(&Method::Get, "/2022/1/1", || Ok(String::new()));
+        assert_eq!(reply.status, 405);
+    }
+
+    #[test]
+    fn bad_input_is_unprocessable() {
+        let (status, body) = post("/2022/01/1", "1000\nlots\n");
+        assert_eq!(status, 422);
+        assert_eq!(body["status"], "failed");
+        assert!(body["error"].as_str().unwrap().contains("line 2"));
+    }
+
+    #[test]
+    fn deep_nesting_is_unprocessable() {
+        let depth = 100_000;
+        let input = format!("{}{}\n[1]\n", "[".repeat(depth), "]".repeat(depth));
+        let (status, body) = post("/2022/13/1", &input);
+        assert_eq!(status, 422);
+        assert!(body["error"].as_str().unwrap().contains("nested"), "{body}");
+    }
+
+    #[test]
+    fn slow_parts_time_out_and_keep_their_worker() {
+        let workers = Workers::new(1);
+        let (release, released) = mpsc::channel::<()>();
+        let slow = move || {
+            released.recv().ok();
+            Outcome::Failed {
+                error: "released".into(),
+            }
+        };
+        assert!(matches!(
+            solve(&workers, Duration::ZERO, slow),
+            Attempt::TimedOut
+        ));
+        assert!(matches!(
+            solve(&workers, TIMEOUT, || unreachable!("every worker is busy")),
+            Attempt::Busy
+        ));
+
+        release.send(()).unwrap();
+        workers.wait_idle();
+        assert!(matches!(
+            solve(&workers, TIMEOUT, || Outcome::Failed {
+                error: "done".into()
+            }),
+            Attempt::Finished(_)
+        ));
+    }
+
+    #[test]
+    fn busy_workers_are_unavailable() {
+        let service = Service::new(0, TIMEOUT);
+        let reply = service.respond(&Method::Post, "/2022/1/1", || Ok(day01::EXAMPLE.into()));
+        assert_eq!(reply.status, 503);
+    }
+}
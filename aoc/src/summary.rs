@@ -0,0 +1,236 @@
+//! Run every part of every day at once, and summarize the results in a table.
+
+use crate::registry::Day;
+use color_eyre::eyre::Result;
+use common::{report::serialize_seconds, Answer, Solution};
+use serde::Serialize;
+use std::{
+    any::Any,
+    fmt,
+    num::NonZeroUsize,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How a single part fared.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum Outcome {
+    Solved { answer: Answer },
+    Failed { error: String },
+    Panicked { error: String },
+}
+
+impl Outcome {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Outcome::Solved { .. })
+    }
+
+    fn status(&self) -> &'static str {
+        match self {
+            Outcome::Solved { .. } => "ok",
+            Outcome::Failed { .. } => "failed",
+            Outcome::Panicked { .. } => "panicked",
+        }
+    }
+}
+
+/// The result of running a single part.
+#[derive(Debug, Clone, Serialize)]
+pub struct Row {
+    pub day: u8,
+    pub part: u8,
+    #[serde(flatten)]
+    pub outcome: Outcome,
+    /// Wall-clock time taken to parse and solve the part, in seconds.
+    #[serde(serialize_with = "serialize_seconds")]
+    pub elapsed: Duration,
+}
+
+impl Row {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("rows always serialize")
+    }
+}
+
+/// A part waiting to be run, or the reason it can't be.
+struct Job {
+    day: &'static Day,
+    part: u8,
+    input: Result<PathBuf, String>,
+}
+
+impl Job {
+    fn run(&self) -> Row {
+        let start = Instant::now();
+        let outcome = match &self.input {
+            Ok(input) => attempt(|| (self.day.solve)(input, self.part, &[])),
+            Err(err) => Outcome::Failed { error: err.clone() },
+        };
+        Row {
+            day: self.day.day,
+            part: self.part,
+            outcome,
+            elapsed: start.elapsed(),
+        }
+    }
+}
+
+/// Run `solve`, recording any error or panic as the outcome instead of propagating it.
+pub fn attempt(solve: impl FnOnce() -> Result<Solution>) -> Outcome {
+    match panic::catch_unwind(AssertUnwindSafe(solve)) {
+        Ok(Ok(solution)) => Outcome::Solved {
+            answer: solution.answer,
+        },
+        Ok(Err(err)) => Outcome::Failed {
+            error: error_chain(&err),
+        },
+        Err(payload) => Outcome::Panicked {
+            error: panic_message(payload.as_ref()),
+        },
+    }
+}
+
+fn error_chain(err: &color_eyre::Report) -> String {
+    err.chain()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(": ")
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panicked with a non-string payload".into())
+}
+
+/// The number of workers to use when none is requested.
+pub fn default_jobs() -> NonZeroUsize {
+    thread::available_parallelism().unwrap_or(NonZeroUsize::MIN)
+}
+
+/// Run both parts of each of `days` on a pool of `jobs` workers.
+///
+/// Inputs are located up front, in order, since that may mean downloading them. Failures,
+/// including panics, are recorded in the returned rows rather than stopping the run. Rows are
+/// sorted by day and part.
+pub fn run(
+    days: &[&'static Day],
+    jobs: NonZeroUsize,
+    locate: impl Fn(&Day) -> Result<PathBuf>,
+) -> Vec<Row> {
+    let queue = days
+        .iter()
+        .flat_map(|&day| {
+            let input = locate(day).map_err(|err| error_chain(&err));
+            [1, 2].map(|part| Job {
+                day,
+                part,
+                input: input.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let next = AtomicUsize::new(0);
+    let rows = Mutex::new(Vec::with_capacity(queue.len()));
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.get().min(queue.len()) {
+            scope.spawn(|| {
+                while let Some(job) = queue.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let row = job.run();
+                    rows.lock()
+                        .expect("workers never panic holding the lock")
+                        .push(row);
+                }
+            });
+        }
+    });
+
+    let mut rows = rows
+        .into_inner()
+        .expect("workers never panic holding the lock");
+    rows.sort_by_key(|row| (row.day, row.part));
+    rows
+}
+
+/// A table of rows, for humans.
+pub struct Table<'a>(pub &'a [Row]);
+
+impl fmt::Display for Table<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "day  part  status    {:>12}  answer", "elapsed")?;
+        for row in self.0 {
+            let elapsed = format!("{:.3?}", row.elapsed);
+            write!(
+                f,
+                "{:02}   {:>4}  {:<8}  {elapsed:>12}  ",
+                row.day,
+                row.part,
+                row.outcome.status(),
+            )?;
+            match &row.outcome {
+                Outcome::Solved {
+                    answer: Answer::Grid(grid),
+                } => {
+                    writeln!(f, "(grid)")?;
+                    for line in grid.lines() {
+                        writeln!(f, "      {line}")?;
+                    }
+                }
+                Outcome::Solved { answer } => writeln!(f, "{answer}")?,
+                Outcome::Failed { error } | Outcome::Panicked { error } => writeln!(f, "{error}")?,
+            }
+        }
+
+        let failed = self.0.iter().filter(|row| !row.outcome.is_ok()).count();
+        let total: Duration = self.0.iter().map(|row| row.elapsed).sum();
+        write!(
+            f,
+            "{} solved, {failed} failed; {total:.3?} of work",
+            self.0.len() - failed
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{input::Example, InputSource};
+
+    #[test]
+    fn failures_do_not_stop_the_run() {
+        let days = crate::registry::DAYS.iter().collect::<Vec<_>>();
+        let rows = run(&days, NonZeroUsize::new(4).unwrap(), |day| {
+            Example(day.example).locate(day.day)
+        });
+
+        assert_eq!(rows.len(), days.len() * 2);
+        assert!(rows
+            .windows(2)
+            .all(|pair| (pair[0].day, pair[0].part) < (pair[1].day, pair[1].part)));
+
+        let [.., day16_part1, day16_part2] = &rows[..] else {
+            panic!("too few rows");
+        };
+        assert_eq!((day16_part1.day, day16_part1.part), (16, 1));
+        assert!(matches!(day16_part1.outcome, Outcome::Failed { .. }));
+        assert_eq!((day16_part2.day, day16_part2.part), (16, 2));
+        let Outcome::Panicked { error } = &day16_part2.outcome else {
+            panic!("day 16 part 2 should panic: {:?}", day16_part2.outcome);
+        };
+        assert!(error.contains("not implemented"), "{error}");
+        assert!(rows[..rows.len() - 2].iter().all(|row| row.outcome.is_ok()));
+
+        let table = Table(&rows).to_string();
+        assert!(table.contains(error.as_str()), "{table}");
+    }
+}
@@ -0,0 +1,95 @@
+//! Re-run a day whenever its input changes.
+
+use common::Answer;
+use std::{fs, path::PathBuf, time::Duration};
+
+/// How often watched files are re-read.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A watched file, and its contents as of the last time it was read.
+pub struct Watched {
+    pub path: PathBuf,
+    /// `None` if the file could not be read.
+    contents: Option<Vec<u8>>,
+}
+
+impl Watched {
+    pub fn new(path: PathBuf) -> Self {
+        let contents = fs::read(&path).ok();
+        Self { path, contents }
+    }
+
+    /// Re-read the file, returning `true` if its contents differ from the last read.
+    ///
+    /// Contents are compared rather than modification times, so saving without changes doesn't
+    /// trigger a re-run.
+    pub fn changed(&mut self) -> bool {
+        let contents = fs::read(&self.path).ok();
+        let changed = contents != self.contents;
+        self.contents = contents;
+        changed
+    }
+}
+
+/// Describe how `current` differs from the `previous` answer for the same part.
+///
+/// `None` if there was no previous answer. Grids are compared row by row.
+pub fn diff(previous: Option<&Answer>, current: &Answer) -> Option<String> {
+    let previous = previous?;
+    if previous == current {
+        return Some("(unchanged)".into());
+    }
+    let (Answer::Grid(previous), Answer::Grid(current)) = (previous, current) else {
+        return Some(format!("(was {previous})"));
+    };
+
+    let mut previous_rows = previous.lines();
+    let mut current_rows = current.lines();
+    let mut out = vec!["(changed)".to_owned()];
+    loop {
+        match (previous_rows.next(), current_rows.next()) {
+            (None, None) => break,
+            (Some(previous), Some(current)) if previous == current => {
+                out.push(format!("  {current}"));
+            }
+            (previous, current) => {
+                out.extend(previous.map(|row| format!("- {row}")));
+                out.extend(current.map(|row| format!("+ {row}")));
+            }
+        }
+    }
+    Some(out.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_run_has_no_diff() {
+        assert_eq!(diff(None, &Answer::from(1)), None);
+    }
+
+    #[test]
+    fn scalar_diff() {
+        let previous = Answer::from("MCD");
+        assert_eq!(
+            diff(Some(&previous), &previous).as_deref(),
+            Some("(unchanged)")
+        );
+        assert_eq!(
+            diff(Some(&previous), &Answer::from("CMZ")).as_deref(),
+            Some("(was MCD)")
+        );
+    }
+
+    #[test]
+    fn grid_diff_marks_changed_rows() {
+        let previous = Answer::Grid("#.\n..\n".into());
+        let current = Answer::Grid("#.\n.#\n##\n".into());
+        assert_eq!(
+            diff(Some(&previous), &current).unwrap(),
+            "(changed)\n  #.\n- ..\n+ .#\n+ ##"
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use serde::Serialize;
+use std::fmt;
+
+/// The answer to a single part of a day's puzzle.
+///
+/// Serializes as a bare number or string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum Answer {
+    /// Most puzzles have a numeric answer.
+    Number(i128),
+    /// Some puzzles want a short string, like the tops of day 5's stacks.
+    Text(String),
+    /// Some puzzles render their answer as a grid of characters, like day 10's CRT.
+    Grid(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => n.fmt(f),
+            Answer::Text(s) | Answer::Grid(s) => s.fmt(f),
+        }
+    }
+}
+
+macro_rules! impl_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Number(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_number!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_owned())
+    }
+}
+
+/// The result of solving a part: the answer, plus any diagnostic lines worth showing alongside it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub answer: Answer,
+    pub extra: Vec<String>,
+}
+
+impl Solution {
+    pub fn new(answer: impl Into<Answer>) -> Self {
+        Self {
+            answer: answer.into(),
+            extra: Vec::new(),
+        }
+    }
+
+    /// Render a grid-shaped answer.
+    pub fn grid(grid: impl ToString) -> Self {
+        Self::new(Answer::Grid(grid.to_string()))
+    }
+
+    /// Append a diagnostic line.
+    pub fn with_extra(mut self, line: impl Into<String>) -> Self {
+        self.extra.push(line.into());
+        self
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{Annotate, Solution};
+use color_eyre::eyre::Result;
+use std::{
+    fmt,
+    hint::black_box,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// How long one run of a part took, split by phase.
+#[derive(Debug, Clone, Copy)]
+pub struct Timing {
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+/// Parse the input and solve a part, timing each phase separately.
+pub fn time<I, E>(
+    input: &Path,
+    parse: impl Fn(&Path) -> Result<I, E>,
+    solve: impl Fn(&I) -> Result<Solution, E>,
+) -> Result<Timing>
+where
+    E: 'static + std::error::Error + Send + Sync,
+{
+    let start = Instant::now();
+    let parsed = black_box(parse(input).annotate()?);
+    let parse_time = start.elapsed();
+
+    let start = Instant::now();
+    black_box(solve(&parsed).annotate()?);
+    let solve_time = start.elapsed();
+
+    Ok(Timing {
+        parse: parse_time,
+        solve: solve_time,
+    })
+}
+
+/// Summary statistics over a set of samples.
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Compute statistics for some samples. `None` if there are no samples.
+    pub fn new(mut samples: Vec<Duration>) -> Option<Self> {
+        samples.sort_unstable();
+        let min = *samples.first()?;
+        let max = *samples.last()?;
+        // for odd lengths, these are the same sample
+        let low_mid = samples[(samples.len() - 1) / 2];
+        let high_mid = samples[samples.len() / 2];
+        let median = (low_mid + high_mid) / 2;
+        Some(Self { min, median, max })
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min {:>10.3?}  median {:>10.3?}  max {:>10.3?}",
+            self.min, self.median, self.max
+        )
+    }
+}
+
+/// Benchmark results for a single part.
+#[derive(Debug, Clone, Copy)]
+pub struct Bench {
+    pub day: u8,
+    pub part: u8,
+    pub runs: usize,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+impl Bench {
+    /// Run `time` repeatedly and summarize the results.
+    ///
+    /// `runs` must be at least 1.
+    pub fn run(day: u8, part: u8, runs: usize, time: impl Fn() -> Result<Timing>) -> Result<Self> {
+        assert!(runs > 0, "benchmarks must run at least once");
+        let mut parse = Vec::with_capacity(runs);
+        let mut solve = Vec::with_capacity(runs);
+        for _ in 0..runs {
+            let timing = time()?;
+            parse.push(timing.parse);
+            solve.push(timing.solve);
+        }
+        Ok(Self {
+            day,
+            part,
+            runs,
+            parse: Stats::new(parse).expect("at least one run"),
+            solve: Stats::new(solve).expect("at least one run"),
+        })
+    }
+
+    pub const CSV_HEADER: &'static str = "timestamp,day,part,runs,phase,min_ns,median_ns,max_ns";
+
+    /// CSV rows for this benchmark: one per phase.
+    ///
+    /// `timestamp` is recorded verbatim so that results from several sessions can share a file.
+    pub fn csv_rows(&self, timestamp: u64) -> impl '_ + Iterator<Item = String> {
+        [("parse", self.parse), ("solve", self.solve)]
+            .into_iter()
+            .map(move |(phase, stats)| {
+                format!(
+                    "{timestamp},{},{},{},{phase},{},{},{}",
+                    self.day,
+                    self.part,
+                    self.runs,
+                    stats.min.as_nanos(),
+                    stats.median.as_nanos(),
+                    stats.max.as_nanos(),
+                )
+            })
+    }
+}
+
+impl fmt::Display for Bench {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "day {:02} part {} ({} runs)",
+            self.day, self.part, self.runs
+        )?;
+        writeln!(f, "  parse: {}", self.parse)?;
+        write!(f, "  solve: {}", self.solve)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn stats_odd() {
+        let stats = Stats::new(ms(&[5, 1, 3])).unwrap();
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.max, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn stats_even() {
+        let stats = Stats::new(ms(&[4, 1, 2, 3])).unwrap();
+        assert_eq!(stats.median, Duration::from_micros(2500));
+    }
+
+    #[test]
+    fn stats_empty() {
+        assert!(Stats::new(Vec::new()).is_none());
+    }
+}
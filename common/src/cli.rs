@@ -0,0 +1,102 @@
+//! The command-line interface shared by every day's binary.
+
+use crate::{report::print_json, Annotate, Answer, Format, InputArgs, LogArgs, Puzzle, Solution};
+use color_eyre::eyre::Result;
+use std::path::Path;
+use structopt::{StructOpt, StructOptInternal};
+
+// The arguments of a day's binary.
+//
+// This isn't a doc comment because structopt would use it as the description of every binary.
+#[derive(StructOpt)]
+struct RunArgs<Params: StructOpt, C: StructOpt> {
+    #[structopt(flatten)]
+    input: InputArgs,
+
+    #[structopt(flatten)]
+    log: LogArgs,
+
+    #[structopt(flatten)]
+    params: Params,
+
+    /// skip part 1
+    #[structopt(long)]
+    no_part1: bool,
+
+    /// run part 2
+    #[structopt(long)]
+    part2: bool,
+
+    /// output format: "text" or "json"
+    #[structopt(long, default_value)]
+    format: Format,
+
+    #[structopt(subcommand)]
+    command: Option<C>,
+}
+
+/// A subcommand which a day's binary offers besides solving its parts.
+pub trait Command<P: Puzzle>: StructOpt {
+    /// Run the command on the selected input.
+    fn run(&self, input: &Path, params: &P::Params) -> Result<()>;
+}
+
+// The subcommands of a day which has none.
+//
+// This isn't a doc comment because structopt would use it as the description of the binary.
+#[derive(Debug, StructOpt)]
+pub enum NoCommand {}
+
+impl<P: Puzzle> Command<P> for NoCommand {
+    fn run(&self, _input: &Path, _params: &P::Params) -> Result<()> {
+        match *self {}
+    }
+}
+
+fn print_text(label: &str, solution: &Solution) {
+    match &solution.answer {
+        Answer::Grid(grid) => println!("{label}:\n{grid}"),
+        answer => println!("{label}: {answer}"),
+    }
+    for line in &solution.extra {
+        println!("  {line}");
+    }
+}
+
+/// Run the binary for `P`: locate the input, then solve the requested parts.
+pub fn main<P: Puzzle>() -> Result<()>
+where
+    P::Params: StructOptInternal,
+{
+    main_with::<P, NoCommand>()
+}
+
+/// Run the binary for `P`, which also offers the subcommands `C`.
+///
+/// Flattening `P::Params` needs structopt's internal trait, which deriving `StructOpt` implements.
+pub fn main_with<P: Puzzle, C: Command<P> + StructOptInternal>() -> Result<()>
+where
+    P::Params: StructOptInternal,
+{
+    color_eyre::install()?;
+    let app = RunArgs::<P::Params, C>::clap().name(format!("day{:02}", P::DAY));
+    let args = RunArgs::<P::Params, C>::from_clap(&app.get_matches());
+    args.log.init()?;
+    let input = args.input.source(P::EXAMPLE).locate(P::DAY)?;
+
+    if let Some(command) = &args.command {
+        return command.run(&input, &args.params);
+    }
+
+    for (part, requested) in [(1, !args.no_part1), (2, args.part2)] {
+        if !requested {
+            continue;
+        }
+        let solve = || P::solve_file(&input, part, &args.params);
+        match args.format {
+            Format::Text => print_text(P::LABELS[usize::from(part - 1)], &solve().annotate()?),
+            Format::Json => print_json(P::DAY, part, solve)?,
+        }
+    }
+    Ok(())
+}
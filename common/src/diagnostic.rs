@@ -0,0 +1,233 @@
+//! Parse errors which know where in the input they occurred.
+//!
+//! Parsers work on slices of the original input, so a [`Diagnostic`] is built from the whole input
+//! and the slice at fault; the line, column, and snippet are all derived from the slice's position.
+
+use color_eyre::{eyre::Report, Section};
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// A parse error located in the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The file the input came from, if any.
+    pub file: Option<PathBuf>,
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, counted in chars.
+    pub column: usize,
+    /// The full line of input containing the error.
+    pub snippet: String,
+    /// The number of chars at fault, starting at `column`.
+    pub width: usize,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Locate an error within `input`.
+    ///
+    /// `span` must be a slice of `input`; otherwise the error is reported at the start of the
+    /// input.
+    pub fn new(input: &str, span: &str, message: impl fmt::Display) -> Self {
+        let start = (span.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|&offset| offset + span.len() <= input.len());
+        let (offset, span_len) = match start {
+            Some(offset) => (offset, span.len()),
+            None => (0, 0),
+        };
+
+        let line_start = input[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |idx| offset + idx);
+        let snippet = input[line_start..line_end].trim_end_matches('\r');
+        let span_end = (offset + span_len).min(line_start + snippet.len());
+
+        Self {
+            file: None,
+            line: input[..offset].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            snippet: snippet.to_owned(),
+            width: input[offset..span_end.max(offset)].chars().count().max(1),
+            message: message.to_string(),
+        }
+    }
+
+    /// Record the file the input came from.
+    pub fn in_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.file = Some(path.into());
+        self
+    }
+
+    /// Render the offending line with a caret under the error.
+    pub fn render(&self) -> String {
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        let location = match &self.file {
+            Some(file) => format!("{}:{}:{}", file.display(), self.line, self.column),
+            None => format!("line {}, column {}", self.line, self.column),
+        };
+        format!(
+            "{gutter}--> {location}\n{gutter} |\n{number} | {}\n{gutter} | {}{}",
+            self.snippet,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.width),
+        )
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at ", self.message)?;
+        match &self.file {
+            Some(file) => write!(f, "{}:{}:{}", file.display(), self.line, self.column),
+            None => write!(f, "line {}, column {}", self.line, self.column),
+        }
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+/// Errors which may carry a [`Diagnostic`].
+pub trait Diagnosed {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic>;
+}
+
+/// Read `path` and parse it with `parse_str`, recording the file name in any diagnostic.
+pub fn parse_file<T, E>(path: &Path, parse_str: impl FnOnce(&str) -> Result<T, E>) -> Result<T, E>
+where
+    E: From<io::Error> + Diagnosed,
+{
+    let input = std::fs::read_to_string(path)?;
+    parse_str(&input).map_err(|mut err| {
+        if let Some(diagnostic) = err.diagnostic_mut() {
+            diagnostic.file = Some(path.to_owned());
+        }
+        err
+    })
+}
+
+/// The trimmed, non-blank lines of `input`, as slices of it.
+pub fn lines(input: &str) -> impl '_ + Iterator<Item = &str> {
+    input.lines().map(str::trim).filter(|line| !line.is_empty())
+}
+
+/// Parse each non-blank line of `input`, locating the first line which fails.
+pub fn parse_lines<T, C>(input: &str) -> Result<C, Diagnostic>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+    C: FromIterator<T>,
+{
+    lines(input)
+        .map(|line| {
+            line.parse().map_err(|err| {
+                Diagnostic::new(
+                    input,
+                    line,
+                    format!("could not parse {}: {err}", std::any::type_name::<T>()),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Check that `input` is a rectangular grid in which every char parses as a `T`.
+///
+/// `aoclib::geometry::Map` doesn't report where a tile failed, so this locates the problem first.
+pub fn check_grid<T>(input: &str) -> Result<(), Diagnostic>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let mut width = None;
+    for line in lines(input) {
+        let line_width = line.chars().count();
+        let expect = *width.get_or_insert(line_width);
+        if line_width != expect {
+            return Err(Diagnostic::new(
+                input,
+                line,
+                format!("row has width {line_width}; expected {expect}"),
+            ));
+        }
+        for (idx, ch) in line.char_indices() {
+            let tile = &line[idx..idx + ch.len_utf8()];
+            if let Err(err) = tile.parse::<T>() {
+                return Err(Diagnostic::new(input, tile, format!("invalid tile: {err}")));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Attach the rendered [`Diagnostic`], if any, to an error as a `color_eyre` section.
+pub trait Annotate<T> {
+    fn annotate(self) -> color_eyre::Result<T>;
+}
+
+impl<T, E> Annotate<T> for Result<T, E>
+where
+    E: Into<Report>,
+{
+    #[track_caller]
+    fn annotate(self) -> color_eyre::Result<T> {
+        let report = match self {
+            Ok(value) => return Ok(value),
+            Err(err) => err.into(),
+        };
+        let rendered = report
+            .chain()
+            .find_map(|err| err.downcast_ref::<Diagnostic>())
+            .map(Diagnostic::render);
+        match rendered {
+            Some(rendered) => Err(report.section(rendered)),
+            None => Err(report),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "1,2\n\n  3,x\n";
+
+    #[test]
+    fn locates_span() {
+        let bad = &INPUT[9..10];
+        assert_eq!(bad, "x");
+        let diagnostic = Diagnostic::new(INPUT, bad, "not a number");
+        assert_eq!((diagnostic.line, diagnostic.column), (3, 5));
+        assert_eq!(diagnostic.snippet, "  3,x");
+        assert_eq!(
+            diagnostic.in_file("input.txt").render(),
+            " --> input.txt:3:5\n  |\n3 |   3,x\n  |     ^"
+        );
+    }
+
+    #[test]
+    fn foreign_span_points_at_start() {
+        let elsewhere = String::from("3,x");
+        let diagnostic = Diagnostic::new(INPUT, &elsewhere, "elsewhere");
+        assert_eq!((diagnostic.line, diagnostic.column), (1, 1));
+    }
+
+    #[test]
+    fn parse_lines_reports_whole_line() {
+        let err = parse_lines::<u32, Vec<_>>("1\n2\n\n  three \n").unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (4, 3, 5));
+    }
+
+    #[test]
+    fn check_grid_finds_bad_tile() {
+        let err = check_grid::<u8>("123\n4x6\n").unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (2, 2, 1));
+        let err = check_grid::<u8>("123\n45\n").unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (2, 1, 2));
+    }
+}
@@ -0,0 +1,338 @@
+//! Where puzzle inputs come from.
+//!
+//! Each source resolves a day to the path of a file containing its input. Sources which don't
+//! naturally live on disk, like stdin or an HTTP response, are written to a file first.
+
+use aoclib::{config::Config, website::get_input};
+use color_eyre::eyre::{bail, Result, WrapErr};
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt,
+    hash::{Hash, Hasher},
+    io::Read,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use structopt::StructOpt;
+
+pub const YEAR: u32 = 2022;
+
+/// Split in-memory input into the blocks separated by blank lines.
+///
+/// This is the string counterpart of `aoclib::input::parse_newline_sep`.
+pub fn blocks(input: &str) -> impl '_ + Iterator<Item = &str> {
+    input.split("\n\n").filter(|block| !block.trim().is_empty())
+}
+
+/// Write each item on its own line, the inverse of splitting an input into lines.
+pub fn join_lines<T: fmt::Display>(items: impl IntoIterator<Item = T>) -> String {
+    items.into_iter().map(|item| format!("{item}\n")).collect()
+}
+
+/// Separate items with blank lines, the inverse of [`blocks`].
+///
+/// Each item's `Display` is expected to end its last line with a newline.
+pub fn join_blocks<T: fmt::Display>(items: impl IntoIterator<Item = T>) -> String {
+    items
+        .into_iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Somewhere puzzle inputs can come from.
+pub trait InputSource {
+    /// Get the path of a file containing the input for `day`.
+    fn locate(&self, day: u8) -> Result<PathBuf>;
+}
+
+/// A directory in the system temp dir for inputs which don't otherwise exist on disk.
+fn scratch_dir() -> Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!("aoc-{YEAR}"));
+    std::fs::create_dir_all(&dir)
+        .wrap_err_with(|| format!("creating scratch dir {}", dir.display()))?;
+    Ok(dir)
+}
+
+/// Write `contents` to a scratch file named for `prefix` and a hash of the contents.
+///
+/// Concurrent runs may write the same file, so each writes a file of its own and renames it into
+/// place: readers see either no file or a complete one, and it always holds `contents`.
+fn write_scratch(prefix: &str, contents: &[u8]) -> Result<PathBuf> {
+    static WRITES: AtomicUsize = AtomicUsize::new(0);
+
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    let dir = scratch_dir()?;
+    let path = dir.join(format!("{prefix}-{:016x}.txt", hasher.finish()));
+    let partial = dir.join(format!(
+        ".{prefix}-{}-{}.partial",
+        std::process::id(),
+        WRITES.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&partial, contents)
+        .wrap_err_with(|| format!("writing {}", partial.display()))?;
+    std::fs::rename(&partial, &path)
+        .wrap_err_with(|| format!("moving {} to {}", partial.display(), path.display()))?;
+    Ok(path)
+}
+
+/// The input file configured for `aoctool`, downloaded from the website if not already present.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AocWebsite;
+
+impl InputSource for AocWebsite {
+    fn locate(&self, day: u8) -> Result<PathBuf> {
+        let config = Config::load()?;
+        // this does nothing if the input file already exists, but
+        // simplifies the workflow after cloning the repo on a new computer
+        get_input(&config, YEAR, day)?;
+        Ok(config.input_for(YEAR, day))
+    }
+}
+
+/// A single, specific file, used whichever day is requested.
+#[derive(Debug, Clone)]
+pub struct File(pub PathBuf);
+
+impl InputSource for File {
+    fn locate(&self, _day: u8) -> Result<PathBuf> {
+        Ok(self.0.clone())
+    }
+}
+
+/// A directory of input files named like `day01.txt`.
+#[derive(Debug, Clone)]
+pub struct CacheDir(pub PathBuf);
+
+impl CacheDir {
+    pub fn path_for(&self, day: u8) -> PathBuf {
+        self.0.join(format!("day{day:02}.txt"))
+    }
+}
+
+impl InputSource for CacheDir {
+    fn locate(&self, day: u8) -> Result<PathBuf> {
+        let path = self.path_for(day);
+        if !path.exists() {
+            bail!("no cached input for day {day} at {}", path.display());
+        }
+        Ok(path)
+    }
+}
+
+/// Standard input.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Stdin;
+
+impl InputSource for Stdin {
+    fn locate(&self, day: u8) -> Result<PathBuf> {
+        let mut contents = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut contents)
+            .wrap_err("reading input from stdin")?;
+        write_scratch(&format!("stdin-day{day:02}"), &contents)
+    }
+}
+
+/// The worked example from the puzzle description, embedded in the day's crate.
+#[derive(Debug, Clone, Copy)]
+pub struct Example(pub &'static str);
+
+impl InputSource for Example {
+    fn locate(&self, day: u8) -> Result<PathBuf> {
+        write_scratch(&format!("example-day{day:02}"), self.0.as_bytes())
+    }
+}
+
+/// An HTTP server following the website's URL scheme: `{base_url}/{year}/day/{day}/input`.
+///
+/// This can be pointed at a local mock server. Responses are cached, so each input is only
+/// requested once.
+#[derive(Debug, Clone)]
+pub struct Http {
+    pub base_url: String,
+    /// Sent as the `session` cookie, if set.
+    pub session: Option<String>,
+    pub cache: CacheDir,
+}
+
+impl Http {
+    pub fn url_for(&self, day: u8) -> String {
+        format!(
+            "{}/{YEAR}/day/{day}/input",
+            self.base_url.trim_end_matches('/')
+        )
+    }
+}
+
+impl InputSource for Http {
+    fn locate(&self, day: u8) -> Result<PathBuf> {
+        let path = self.cache.path_for(day);
+        if path.exists() {
+            return Ok(path);
+        }
+
+        let url = self.url_for(day);
+        let mut request = ureq::get(&url);
+        if let Some(session) = &self.session {
+            request = request.set("Cookie", &format!("session={session}"));
+        }
+        let contents = request
+            .call()
+            .wrap_err_with(|| format!("requesting {url}"))?
+            .into_string()
+            .wrap_err_with(|| format!("reading response from {url}"))?;
+
+        std::fs::create_dir_all(&self.cache.0)
+            .wrap_err_with(|| format!("creating cache dir {}", self.cache.0.display()))?;
+        std::fs::write(&path, contents).wrap_err_with(|| format!("writing {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+// Command-line options selecting an input source.
+//
+// This isn't a doc comment because structopt would use it as the description of every binary which
+// flattens these options into its own.
+#[derive(StructOpt, Debug, Default, Clone)]
+pub struct InputArgs {
+    /// input file, or "-" to read from stdin
+    #[structopt(long, parse(from_os_str))]
+    pub input: Option<PathBuf>,
+
+    /// use the worked example from the puzzle description as input
+    #[structopt(long, conflicts_with_all = &["input", "input-url"])]
+    pub example: bool,
+
+    /// directory of cached input files named like "day01.txt"
+    #[structopt(long, parse(from_os_str))]
+    pub input_dir: Option<PathBuf>,
+
+    /// fetch inputs from this base URL, caching them in --input-dir if set; sends $AOC_SESSION as the session cookie
+    #[structopt(long, conflicts_with = "input")]
+    pub input_url: Option<String>,
+}
+
+impl InputArgs {
+    /// `true` when the selected source only makes sense for a single day.
+    pub fn is_single_day(&self) -> bool {
+        self.input.is_some()
+    }
+
+    /// The selected input source. `example` is the day's embedded example.
+    pub fn source(&self, example: &'static str) -> Box<dyn InputSource> {
+        if self.example {
+            return Box::new(Example(example));
+        }
+        if let Some(input) = &self.input {
+            return if input == Path::new("-") {
+                Box::new(Stdin)
+            } else {
+                Box::new(File(input.clone()))
+            };
+        }
+        if let Some(base_url) = &self.input_url {
+            let cache = match &self.input_dir {
+                Some(dir) => dir.clone(),
+                None => std::env::temp_dir()
+                    .join(format!("aoc-{YEAR}"))
+                    .join("http"),
+            };
+            return Box::new(Http {
+                base_url: base_url.clone(),
+                session: std::env::var("AOC_SESSION").ok(),
+                cache: CacheDir(cache),
+            });
+        }
+        if let Some(dir) = &self.input_dir {
+            return Box::new(CacheDir(dir.clone()));
+        }
+        Box::new(AocWebsite)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+    };
+
+    #[test]
+    fn blocks_skip_blank_runs() {
+        let input = "1\n2\n\n3\n\n\n\n4\n";
+        assert_eq!(blocks(input).collect::<Vec<_>>(), ["1\n2", "3", "4\n"]);
+    }
+
+    #[test]
+    fn example_is_written_to_disk() {
+        let path = Example("1\n2\n").locate(99).unwrap();
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "1\n2\n");
+    }
+
+    #[test]
+    fn concurrent_scratch_writes_do_not_mix() {
+        let writers = (0..8)
+            .map(|writer| {
+                std::thread::spawn(move || {
+                    let contents = format!("{writer}\n").repeat(10_000);
+                    for _ in 0..10 {
+                        let path = write_scratch("scratch-test", contents.as_bytes()).unwrap();
+                        assert_eq!(std::fs::read_to_string(path).unwrap(), contents);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn cache_dir_requires_existing_file() {
+        let dir = scratch_dir().unwrap().join("empty-cache");
+        assert!(CacheDir(dir).locate(1).is_err());
+    }
+
+    #[test]
+    fn http_fetches_from_mock_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            // drain the headers
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            let body = "mock input\n";
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            request_line
+        });
+
+        let cache = scratch_dir()
+            .unwrap()
+            .join(format!("http-test-{}", addr.port()));
+        let source = Http {
+            base_url: format!("http://{addr}/"),
+            session: None,
+            cache: CacheDir(cache),
+        };
+        let path = source.locate(7).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "mock input\n");
+        assert!(server.join().unwrap().starts_with("GET /2022/day/7/input "));
+
+        // now it's cached, so this doesn't need the server
+        assert_eq!(source.locate(7).unwrap(), path);
+    }
+}
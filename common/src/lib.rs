@@ -0,0 +1,22 @@
+//! Shared infrastructure for the daily solutions.
+
+mod answer;
+pub mod bench;
+pub mod cli;
+pub mod diagnostic;
+pub mod input;
+pub mod logging;
+pub mod memory;
+pub mod puzzle;
+pub mod repl;
+pub mod report;
+pub mod visualize;
+
+pub use answer::{Answer, Solution};
+pub use cli::{main, main_with};
+pub use diagnostic::{Annotate, Diagnostic};
+pub use input::{InputArgs, InputSource};
+pub use logging::LogArgs;
+pub use puzzle::Puzzle;
+pub use report::{Format, Report};
+pub use visualize::VisualizeArgs;
@@ -0,0 +1,87 @@
+//! Structured diagnostics emitted while solving, via [`tracing`].
+//!
+//! Days emit events rather than printing to stderr; a runner installs a subscriber with
+//! [`LogArgs::init`] to decide which of them are shown, and how.
+
+use color_eyre::eyre::{eyre, Result};
+use std::{fmt, str::FromStr};
+use structopt::StructOpt;
+use tracing::Span;
+use tracing_error::ErrorLayer;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+/// How log events are written to stderr.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable, multi-line text.
+    #[default]
+    Pretty,
+    /// One JSON object per line.
+    Json,
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogFormat::Pretty => write!(f, "pretty"),
+            LogFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "pretty" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("expected \"pretty\" or \"json\"; got {s:?}")),
+        }
+    }
+}
+
+// Flattened into each binary's arguments.
+//
+// `--log` accepts a level ("debug") or a filter directive ("day11=trace,warn").
+#[derive(Debug, Clone, StructOpt)]
+pub struct LogArgs {
+    /// log level or filter directives for events written to stderr
+    #[structopt(long, value_name = "FILTER", default_value = "warn")]
+    pub log: String,
+
+    /// log format: "pretty" or "json"
+    #[structopt(long, default_value)]
+    pub log_format: LogFormat,
+}
+
+impl LogArgs {
+    /// Install the global subscriber described by these args.
+    pub fn init(&self) -> Result<()> {
+        let filter = EnvFilter::try_new(&self.log)
+            .map_err(|err| eyre!("invalid --log filter {:?}: {err}", self.log))?;
+        let builder = tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_writer(std::io::stderr);
+        // the error layer lets `color_eyre` reports show the spans in which they arose
+        match self.log_format {
+            LogFormat::Pretty => builder
+                .pretty()
+                .finish()
+                .with(ErrorLayer::default())
+                .try_init(),
+            LogFormat::Json => builder
+                .json()
+                .flatten_event(true)
+                .finish()
+                .with(ErrorLayer::default())
+                .try_init(),
+        }
+        .map_err(|err| eyre!(err))
+    }
+}
+
+/// The span within which a single part is solved.
+pub fn part_span(day: u8, part: u8) -> Span {
+    tracing::info_span!("part", day, part)
+}
@@ -0,0 +1,130 @@
+//! Count heap allocations, to spot parts which use more memory than they should.
+//!
+//! [`CountingAllocator`] only counts anything once a binary installs it as its global allocator,
+//! which the runner does behind its `count-allocations` feature. The counters are global, so
+//! [`measure`] is only meaningful while nothing else is allocating.
+
+use serde::Serialize;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Bytes currently allocated.
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+/// The most bytes allocated at once since the last reset.
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+/// Calls to `alloc` and `realloc`.
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator, counting what passes through it.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn grow(&self, size: usize) {
+        let allocated = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(allocated, Ordering::Relaxed);
+    }
+
+    fn shrink(&self, size: usize) {
+        ALLOCATED.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        self.shrink(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            if new_size > layout.size() {
+                self.grow(new_size - layout.size());
+            } else {
+                self.shrink(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+/// How much heap memory some code used.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Usage {
+    /// The most bytes it had allocated at once, beyond what was allocated before it started.
+    pub peak_bytes: usize,
+    /// How many times it allocated or reallocated.
+    pub allocations: usize,
+}
+
+impl fmt::Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+        let mut peak = self.peak_bytes as f64;
+        let mut unit = 0;
+        while peak >= 1024.0 && unit < UNITS.len() - 1 {
+            peak /= 1024.0;
+            unit += 1;
+        }
+        write!(
+            f,
+            "peak {peak:.1} {} over {} allocations",
+            UNITS[unit], self.allocations
+        )
+    }
+}
+
+/// Run `f`, measuring its heap usage.
+///
+/// Only meaningful when [`CountingAllocator`] is the global allocator; otherwise the usage is zero.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Usage) {
+    let baseline = ALLOCATED.load(Ordering::Relaxed);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+
+    let value = f();
+
+    let usage = Usage {
+        peak_bytes: PEAK.load(Ordering::Relaxed).saturating_sub(baseline),
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+    };
+    (value, usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `measure` is tested in `tests/memory.rs`, where nothing else allocates concurrently
+
+    #[test]
+    fn display() {
+        let usage = Usage {
+            peak_bytes: 3 << 19,
+            allocations: 12,
+        };
+        assert_eq!(usage.to_string(), "peak 1.5 MiB over 12 allocations");
+    }
+}
@@ -0,0 +1,118 @@
+//! The interface every day implements, so that tooling can be written once for all of them.
+
+use crate::{
+    diagnostic::{self, Diagnosed},
+    logging, Solution,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::{io, ops::Range, path::Path};
+use structopt::StructOpt;
+
+/// A day's puzzle.
+pub trait Puzzle {
+    /// The day of the month on which this puzzle was released.
+    const DAY: u8;
+    /// The worked example from the puzzle description.
+    const EXAMPLE: &'static str;
+    /// What each part's answer is, as its binary prints it.
+    const LABELS: [&'static str; 2] = ["part 1", "part 2"];
+
+    /// The model which both parts solve from.
+    type Input;
+    /// Settings accepted on the command line in addition to the input.
+    type Params: StructOpt + Default;
+    type Answer1: Into<Solution>;
+    type Answer2: Into<Solution>;
+    type Error: 'static + std::error::Error + Send + Sync + From<io::Error> + Diagnosed;
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error>;
+    fn part1(input: &Self::Input, params: &Self::Params) -> Result<Self::Answer1, Self::Error>;
+    fn part2(input: &Self::Input, params: &Self::Params) -> Result<Self::Answer2, Self::Error>;
+
+    /// Generate a random input which parses and solves with the default params.
+    ///
+    /// `size` scales the input; what it counts varies by day.
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String;
+
+    /// Write an input back out in the puzzle's format.
+    ///
+    /// Parsing the rendered text gives back the same input. The text itself may differ from what
+    /// was parsed: day 7, for example, renders a canonical transcript of its filesystem.
+    fn render(input: &Self::Input) -> String;
+
+    /// Parse an input file, recording its name in any diagnostic.
+    fn parse_file(path: &Path) -> Result<Self::Input, Self::Error> {
+        diagnostic::parse_file(path, Self::parse)
+    }
+
+    /// Solve one part from a parsed input.
+    ///
+    /// Panics if `part` is neither 1 nor 2.
+    fn solve(
+        input: &Self::Input,
+        part: u8,
+        params: &Self::Params,
+    ) -> Result<Solution, Self::Error> {
+        let _span = logging::part_span(Self::DAY, part).entered();
+        match part {
+            1 => Self::part1(input, params).map(Into::into),
+            2 => Self::part2(input, params).map(Into::into),
+            _ => panic!("puzzles have only two parts; requested part {part}"),
+        }
+    }
+
+    /// Solve one part straight from an input file.
+    ///
+    /// By default this parses the whole file first; days which can do better override it.
+    fn solve_file(path: &Path, part: u8, params: &Self::Params) -> Result<Solution, Self::Error> {
+        Self::solve(&Self::parse_file(path)?, part, params)
+    }
+
+    /// Parse the params for this puzzle from arguments, as they would be given to its binary.
+    fn parse_params<'a>(
+        args: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Self::Params, structopt::clap::Error> {
+        let name = format!("day{:02}", Self::DAY);
+        Self::Params::from_iter_safe(
+            std::iter::once(name).chain(args.into_iter().map(str::to_owned)),
+        )
+    }
+}
+
+/// Generate an input for `P` from a seed.
+///
+/// The same seed always generates the same input.
+pub fn generate_seeded<P: Puzzle>(seed: u64, size: usize) -> String {
+    P::generate(&mut ChaCha8Rng::seed_from_u64(seed), size)
+}
+
+/// Assert that parsing a rendered input gives back the same input, for the example and for inputs
+/// generated from each of `seeds`.
+///
+/// For use in each day's tests.
+pub fn assert_round_trips<P: Puzzle>(seeds: Range<u64>, size: usize)
+where
+    P::Input: PartialEq,
+{
+    let inputs = std::iter::once((None, P::EXAMPLE.to_owned()))
+        .chain(seeds.map(|seed| (Some(seed), generate_seeded::<P>(seed, size))));
+    for (seed, input) in inputs {
+        let parsed = P::parse(&input)
+            .unwrap_or_else(|err| panic!("seed {seed:?}: failed to parse input: {err}"));
+        let rendered = P::render(&parsed);
+        let reparsed = P::parse(&rendered).unwrap_or_else(|err| {
+            panic!("seed {seed:?}: failed to re-parse rendered input: {err}")
+        });
+        assert!(
+            reparsed == parsed,
+            "seed {seed:?}: rendered input parses differently\n--- parsed\n{input}\n--- rendered\n{rendered}"
+        );
+    }
+}
+
+// The params of a puzzle which takes none.
+//
+// This isn't a doc comment because structopt would use it as the description of the binary.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, StructOpt)]
+pub struct NoParams {}
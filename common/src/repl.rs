@@ -0,0 +1,443 @@
+//! Step through a simulation interactively.
+//!
+//! A day describes one step of its simulation by implementing [`Simulation`], and [`run`] reads
+//! commands which step it forward, run it until a condition holds, print it, or rewind it.
+//!
+//! Rewinding restores the nearest earlier checkpoint and replays forward from there, so
+//! simulations must be deterministic.
+
+use std::{
+    fmt,
+    io::{self, BufRead, Write},
+    str::FromStr,
+};
+
+/// A checkpoint is kept every this many steps.
+const CHECKPOINT_INTERVAL: usize = 64;
+
+/// A simulation which advances one step at a time.
+///
+/// Its `Display` impl is what the `print` command shows.
+pub trait Simulation: Clone + fmt::Display {
+    type Error: std::error::Error;
+
+    /// Advance by one step.
+    ///
+    /// `Ok(false)` if the simulation had already finished, in which case nothing changed.
+    fn step(&mut self) -> Result<bool, Self::Error>;
+
+    /// Named measurements of the current state, which `until` conditions can test.
+    fn probes(&self) -> Vec<(String, i64)>;
+}
+
+/// A puzzle whose solution can be stepped through.
+pub trait Interactive: crate::Puzzle {
+    type Simulation: Simulation;
+
+    /// The simulation which solves `part`, before its first step.
+    fn simulate(input: &Self::Input, part: u8) -> Self::Simulation;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    fn holds(self, left: i64, right: i64) -> bool {
+        match self {
+            Comparison::Eq => left == right,
+            Comparison::Ne => left != right,
+            Comparison::Lt => left < right,
+            Comparison::Le => left <= right,
+            Comparison::Gt => left > right,
+            Comparison::Ge => left >= right,
+        }
+    }
+}
+
+impl FromStr for Comparison {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "==" | "=" => Comparison::Eq,
+            "!=" => Comparison::Ne,
+            "<" => Comparison::Lt,
+            "<=" => Comparison::Le,
+            ">" => Comparison::Gt,
+            ">=" => Comparison::Ge,
+            _ => return Err(format!("unknown comparison {s:?}")),
+        })
+    }
+}
+
+/// When to stop running.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    /// Once the simulation has finished.
+    Done,
+    /// Once a probe compares as given against a value.
+    Probe {
+        name: String,
+        comparison: Comparison,
+        value: i64,
+    },
+}
+
+impl Condition {
+    /// `Err` if the condition names a probe which the simulation doesn't have.
+    fn holds(&self, simulation: &impl Simulation) -> Result<bool, String> {
+        let Condition::Probe {
+            name,
+            comparison,
+            value,
+        } = self
+        else {
+            return Ok(false);
+        };
+        let probes = simulation.probes();
+        let (_, measured) = probes
+            .iter()
+            .find(|(probe, _)| probe == name)
+            .ok_or_else(|| format!("no probe named {name:?}; try one of: {}", names(&probes)))?;
+        Ok(comparison.holds(*measured, *value))
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_whitespace().collect::<Vec<_>>()[..] {
+            ["done"] => Ok(Condition::Done),
+            [name, comparison, value] => Ok(Condition::Probe {
+                name: name.to_owned(),
+                comparison: comparison.parse()?,
+                value: value
+                    .parse()
+                    .map_err(|err| format!("parsing {value:?}: {err}"))?,
+            }),
+            _ => Err("expected \"done\" or \"PROBE OP VALUE\", like \"cycle >= 20\"".into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Command {
+    Step(usize),
+    Until(Condition),
+    Back(usize),
+    Goto(usize),
+    Print,
+    Help,
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (command, rest) = s.trim().split_once(' ').unwrap_or((s.trim(), ""));
+        let rest = rest.trim();
+        let count = |default| {
+            if rest.is_empty() {
+                Ok(default)
+            } else {
+                rest.parse()
+                    .map_err(|err| format!("parsing {rest:?} as a step count: {err}"))
+            }
+        };
+        match command {
+            "step" | "s" => count(1).map(Command::Step),
+            "until" | "u" => rest.parse().map(Command::Until),
+            "back" | "b" => count(1).map(Command::Back),
+            "goto" | "g" if !rest.is_empty() => count(0).map(Command::Goto),
+            "goto" | "g" => Err("goto needs a step number".into()),
+            "print" | "p" => Ok(Command::Print),
+            "help" | "h" | "?" => Ok(Command::Help),
+            "quit" | "q" => Ok(Command::Quit),
+            _ => Err(format!("unknown command {command:?}; try \"help\"")),
+        }
+    }
+}
+
+const HELP: &str = "\
+step [N]              advance N steps (default 1)
+until done            advance until the simulation finishes
+until PROBE OP VALUE  advance until a probe compares as given; OP is one of == != < <= > >=
+back [N]              rewind N steps (default 1)
+goto N                rewind or advance to step N
+print                 show the current state
+help                  show this message
+quit                  stop
+
+An empty line repeats the previous command.";
+
+fn names(probes: &[(String, i64)]) -> String {
+    probes
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A simulation, how far it's got, and where it's been.
+struct Session<S> {
+    current: S,
+    step: usize,
+    /// `checkpoints[n]` is the state after `n * CHECKPOINT_INTERVAL` steps.
+    checkpoints: Vec<S>,
+}
+
+impl<S: Simulation> Session<S> {
+    fn new(initial: S) -> Self {
+        Self {
+            current: initial.clone(),
+            step: 0,
+            checkpoints: vec![initial],
+        }
+    }
+
+    /// Advance one step; `Ok(false)` if the simulation had already finished.
+    fn step(&mut self) -> Result<bool, S::Error> {
+        if !self.current.step()? {
+            return Ok(false);
+        }
+        self.step += 1;
+        if self.step.is_multiple_of(CHECKPOINT_INTERVAL)
+            && self.checkpoints.len() == self.step / CHECKPOINT_INTERVAL
+        {
+            self.checkpoints.push(self.current.clone());
+        }
+        Ok(true)
+    }
+
+    /// Advance while `keep_going` holds and the simulation hasn't finished, returning how many steps
+    /// were taken.
+    fn advance_while(
+        &mut self,
+        mut keep_going: impl FnMut(&Self) -> Result<bool, String>,
+    ) -> Result<usize, String> {
+        let mut taken = 0;
+        while keep_going(self)? {
+            if !self.step().map_err(|err| err.to_string())? {
+                break;
+            }
+            taken += 1;
+        }
+        Ok(taken)
+    }
+
+    /// Go to an earlier step by replaying from the last checkpoint before it.
+    fn rewind(&mut self, to: usize) -> Result<(), S::Error> {
+        let checkpoint = to / CHECKPOINT_INTERVAL;
+        self.current = self.checkpoints[checkpoint].clone();
+        self.step = checkpoint * CHECKPOINT_INTERVAL;
+        while self.step < to {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    fn status(&self) -> String {
+        let probes = self
+            .current
+            .probes()
+            .into_iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!("step {}: {probes}", self.step)
+    }
+
+    /// Carry out a command, returning what to show for it.
+    fn execute(&mut self, command: &Command) -> Result<String, String> {
+        match command {
+            Command::Step(n) => {
+                let to = self.step.saturating_add(*n);
+                let taken = self.advance_while(|session| Ok(session.step < to))?;
+                if taken < *n {
+                    return Ok(format!("finished after {taken} step(s)\n{}", self.status()));
+                }
+            }
+            Command::Until(condition) => {
+                let taken =
+                    self.advance_while(|session| Ok(!condition.holds(&session.current)?))?;
+                if *condition != Condition::Done && !condition.holds(&self.current)? {
+                    return Ok(format!(
+                        "finished after {taken} step(s) without the condition holding\n{}",
+                        self.status()
+                    ));
+                }
+            }
+            Command::Back(n) => {
+                let to = self.step.saturating_sub(*n);
+                self.rewind(to).map_err(|err| err.to_string())?;
+            }
+            Command::Goto(to) if *to < self.step => {
+                self.rewind(*to).map_err(|err| err.to_string())?;
+            }
+            Command::Goto(to) => {
+                let to = *to;
+                self.advance_while(|session| Ok(session.step < to))?;
+                if self.step < to {
+                    return Ok(format!("finished at {}", self.status()));
+                }
+            }
+            Command::Print => return Ok(format!("{}\n{}", self.current, self.status())),
+            Command::Help => return Ok(HELP.into()),
+            Command::Quit => return Ok(String::new()),
+        }
+        Ok(self.status())
+    }
+}
+
+/// Read commands from `input` until it ends or says to quit, writing responses to `output`.
+pub fn run<S: Simulation>(
+    simulation: S,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    let mut session = Session::new(simulation);
+    let mut previous = None;
+
+    writeln!(output, "{}\n\"help\" lists the commands", session.status())?;
+    write!(output, "> ")?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        let command = if line.trim().is_empty() {
+            previous
+                .clone()
+                .ok_or_else(|| "no previous command".to_owned())
+        } else {
+            line.parse::<Command>()
+        };
+        match command.and_then(|command| {
+            let response = session.execute(&command);
+            previous = Some(command);
+            response
+        }) {
+            Ok(_) if previous == Some(Command::Quit) => return Ok(()),
+            Ok(response) => writeln!(output, "{response}")?,
+            Err(err) => writeln!(output, "error: {err}")?,
+        }
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+    writeln!(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+
+    /// Counts up to a limit.
+    #[derive(Clone)]
+    struct Counter {
+        count: i64,
+        limit: i64,
+    }
+
+    impl fmt::Display for Counter {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "counted to {}", self.count)
+        }
+    }
+
+    impl Simulation for Counter {
+        type Error = Infallible;
+
+        fn step(&mut self) -> Result<bool, Infallible> {
+            let advanced = self.count < self.limit;
+            self.count += i64::from(advanced);
+            Ok(advanced)
+        }
+
+        fn probes(&self) -> Vec<(String, i64)> {
+            vec![("count".into(), self.count)]
+        }
+    }
+
+    fn session(script: &str) -> Vec<String> {
+        let counter = Counter {
+            count: 0,
+            limit: 200,
+        };
+        let mut output = Vec::new();
+        run(counter, script.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .split("> ")
+            .skip(1)
+            .map(|response| response.trim_end().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn steps_and_runs_until() {
+        let responses = session("step\n\nstep 3\nuntil count >= 100\nprint\n");
+        assert_eq!(
+            responses,
+            [
+                "step 1: count=1",
+                "step 2: count=2",
+                "step 5: count=5",
+                "step 100: count=100",
+                "counted to 100\nstep 100: count=100",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn rewinds_across_checkpoints() {
+        let responses = session("goto 150\nback 100\ngoto 3\nback 10\nstep 2\n");
+        assert_eq!(
+            responses,
+            [
+                "step 150: count=150",
+                "step 50: count=50",
+                "step 3: count=3",
+                "step 0: count=0",
+                "step 2: count=2",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn huge_steps_run_to_the_end() {
+        let responses = session(&format!("step 100\nstep {}\n", usize::MAX));
+        assert_eq!(
+            responses,
+            [
+                "step 100: count=100",
+                "finished after 100 step(s)\nstep 200: count=200",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_finishing_and_errors() {
+        let responses = session("until done\nstep\nuntil missing == 1\nfly\nq\nprint\n");
+        assert_eq!(
+            responses,
+            [
+                "step 200: count=200",
+                "finished after 0 step(s)\nstep 200: count=200",
+                "error: no probe named \"missing\"; try one of: count",
+                "error: unknown command \"fly\"; try \"help\"",
+                "",
+            ]
+        );
+    }
+}
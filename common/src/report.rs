@@ -0,0 +1,129 @@
+use crate::{memory::Usage, Annotate, Answer, Solution};
+use color_eyre::eyre::Result;
+use serde::{Serialize, Serializer};
+use std::{
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+/// How the binaries emit their answers.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Human-readable text, worded differently per day.
+    #[default]
+    Text,
+    /// One JSON object per part, in a format which is stable across days.
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!(
+                "unknown format {s:?}; expected \"text\" or \"json\""
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Text => f.write_str("text"),
+            Format::Json => f.write_str("json"),
+        }
+    }
+}
+
+/// Serialize a duration as fractional seconds.
+pub fn serialize_seconds<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+/// A machine-readable record of a solved part.
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Answer,
+    /// Wall-clock time taken to solve the part, in seconds.
+    #[serde(serialize_with = "serialize_seconds")]
+    pub elapsed: Duration,
+    pub extra: Vec<String>,
+    /// Heap usage while solving the part, if it was measured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Usage>,
+}
+
+impl Report {
+    pub fn new(day: u8, part: u8, solution: Solution, elapsed: Duration) -> Self {
+        Self {
+            day,
+            part,
+            answer: solution.answer,
+            elapsed,
+            extra: solution.extra,
+            memory: None,
+        }
+    }
+
+    pub fn with_memory(mut self, memory: Option<Usage>) -> Self {
+        self.memory = memory;
+        self
+    }
+
+    /// Solve a part, recording how long it took.
+    pub fn timed<E>(day: u8, part: u8, solve: impl FnOnce() -> Result<Solution, E>) -> Result<Self>
+    where
+        E: 'static + std::error::Error + Send + Sync,
+    {
+        let start = Instant::now();
+        let solution = solve().annotate()?;
+        Ok(Self::new(day, part, solution, start.elapsed()))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("reports always serialize")
+    }
+}
+
+/// Solve a part and print its report as a line of JSON.
+pub fn print_json<E>(day: u8, part: u8, solve: impl FnOnce() -> Result<Solution, E>) -> Result<()>
+where
+    E: 'static + std::error::Error + Send + Sync,
+{
+    println!("{}", Report::timed(day, part, solve)?.to_json());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_shape() {
+        let solution = Solution::new(24_000_u32).with_extra("note");
+        let report = Report::new(1, 2, solution, Duration::from_millis(1500));
+        assert_eq!(
+            report.to_json(),
+            r#"{"day":1,"part":2,"answer":24000,"elapsed":1.5,"extra":["note"]}"#
+        );
+
+        let memory = Usage {
+            peak_bytes: 2048,
+            allocations: 3,
+        };
+        assert!(report
+            .with_memory(Some(memory))
+            .to_json()
+            .ends_with(r#""extra":["note"],"memory":{"peak_bytes":2048,"allocations":3}}"#));
+    }
+}
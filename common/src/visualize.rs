@@ -0,0 +1,186 @@
+//! Watch grid-based simulations: print them, render them to images, or animate them.
+//!
+//! A day describes how to draw its state by implementing [`Draw`], and drives a set of [`Hooks`]
+//! through its simulation: [`Hooks::pre`] before it starts, [`Hooks::trace`] after each step, and
+//! [`Hooks::post`] once it's done. The hooks do nothing unless [`VisualizeArgs`] asks for them,
+//! and only draw frames when they're needed, so simulations can call them unconditionally.
+
+use aoclib::geometry::{
+    map::{Animation, EncodingError, RenderError, Style},
+    tile::ToRgb,
+    Map,
+};
+use std::{
+    borrow::Cow,
+    fmt,
+    num::{NonZeroU32, NonZeroUsize},
+    path::PathBuf,
+    time::Duration,
+};
+use structopt::StructOpt;
+
+// Ways to watch a simulation.
+//
+// This isn't a doc comment because structopt would use it as the description of every binary which
+// flattens these options into its own.
+#[derive(Debug, Clone, PartialEq, Eq, StructOpt)]
+pub struct VisualizeArgs {
+    /// print the grid before the simulation starts
+    #[structopt(long)]
+    pub console_pre: bool,
+
+    /// print the grid once the simulation is done
+    #[structopt(long)]
+    pub console_post: bool,
+
+    /// render the grid before the simulation starts to this image
+    #[structopt(long, parse(from_os_str))]
+    pub image_pre: Option<PathBuf>,
+
+    /// render the grid once the simulation is done to this image
+    #[structopt(long, parse(from_os_str))]
+    pub image_post: Option<PathBuf>,
+
+    /// animate the simulation into this file
+    #[structopt(long, parse(from_os_str))]
+    pub animation: Option<PathBuf>,
+
+    /// animation frame rate
+    #[structopt(long, default_value = "60")]
+    pub fps: NonZeroU32,
+
+    /// only animate every nth step of the simulation
+    #[structopt(long)]
+    pub every_n_frames: Option<NonZeroUsize>,
+}
+
+impl Default for VisualizeArgs {
+    fn default() -> Self {
+        Self {
+            console_pre: false,
+            console_post: false,
+            image_pre: None,
+            image_post: None,
+            animation: None,
+            fps: NonZeroU32::new(60).expect("60 is not 0"),
+            every_n_frames: None,
+        }
+    }
+}
+
+impl VisualizeArgs {
+    /// `true` if any output was requested.
+    ///
+    /// Days whose drawable state costs something to maintain can skip it when this is `false`.
+    pub fn is_enabled(&self) -> bool {
+        self.console_pre
+            || self.console_post
+            || self.image_pre.is_some()
+            || self.image_post.is_some()
+            || self.animation.is_some()
+    }
+}
+
+/// Simulation state which can be drawn as a grid.
+///
+/// Its `Display` impl is what the console hooks print.
+pub trait Draw: fmt::Display {
+    type Tile: ToRgb + Clone;
+
+    /// The grid to render as an image or animation frame.
+    ///
+    /// Every frame of a single simulation must have the same dimensions.
+    fn frame(&self) -> Cow<'_, Map<Self::Tile>>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("rendering image")]
+    Rendering(#[from] RenderError),
+    #[error("adding frame to animation")]
+    Animating(#[from] EncodingError),
+}
+
+struct AnimationState {
+    animation: Animation,
+    frame: usize,
+    every_n_frames: Option<NonZeroUsize>,
+}
+
+/// Observers of a single simulation.
+pub struct Hooks<'a> {
+    args: &'a VisualizeArgs,
+    animation: Option<AnimationState>,
+}
+
+impl<'a> Hooks<'a> {
+    /// Run before the simulation starts.
+    pub fn pre<D: Draw>(state: &D, args: &'a VisualizeArgs) -> Result<Self, Error> {
+        if args.console_pre {
+            println!("{state}");
+        }
+
+        if let Some(path) = &args.image_pre {
+            state.frame().render(path, Style::Grid)?;
+        }
+
+        let mut animation = None;
+        if let Some(path) = &args.animation {
+            let fps = args.fps.get();
+            let frame = state.frame();
+
+            // hold the first and last frames for a second each, at half speed
+            let mut writer =
+                frame.prepare_animation(path, Duration::from_secs(1) / fps / 2, Style::Grid)?;
+            for _ in 0..fps {
+                writer.write_frame(&frame)?;
+            }
+
+            animation = Some(AnimationState {
+                animation: writer,
+                frame: 0,
+                every_n_frames: args.every_n_frames,
+            });
+        }
+
+        Ok(Self { args, animation })
+    }
+
+    /// Run after each step of the simulation.
+    pub fn trace<D: Draw>(&mut self, state: &D) -> Result<(), Error> {
+        if let Some(animation) = self.animation.as_mut() {
+            if animation.frame == 0 {
+                animation.animation.write_frame(&state.frame())?;
+            }
+            animation.frame += 1;
+            if let Some(n) = animation.every_n_frames {
+                animation.frame %= n.get();
+            } else {
+                animation.frame = 0;
+            }
+        }
+        Ok(())
+    }
+
+    /// Run once the simulation is done.
+    ///
+    /// This consumes the hooks so that the animation is written now, not at program exit.
+    pub fn post<D: Draw>(self, state: &D) -> Result<(), Error> {
+        if self.args.console_post {
+            println!("{state}");
+        }
+
+        if let Some(path) = &self.args.image_post {
+            state.frame().render(path, Style::Grid)?;
+        }
+
+        if let Some(mut animation) = self.animation {
+            let frame = state.frame();
+            for _ in 0..self.args.fps.get() {
+                animation.animation.write_frame(&frame)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+//! `measure` reads global counters, so it is tested in its own binary, where this is the only test
+//! and nothing else allocates while it runs.
+
+use common::memory::{measure, CountingAllocator};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn measures_peak_and_count() {
+    let ((), usage) = measure(|| {
+        let big = vec![0_u8; 1 << 20];
+        drop(std::hint::black_box(big));
+        let mut small = Vec::<u64>::new();
+        for n in 0..100 {
+            small.push(n);
+        }
+        std::hint::black_box(small);
+    });
+    assert!(usage.peak_bytes >= 1 << 20, "{usage:?}");
+    assert!(usage.peak_bytes < 2 << 20, "{usage:?}");
+    // one allocation for `big`, then one per growth of `small`
+    assert!((2..=10).contains(&usage.allocations), "{usage:?}");
+}
@@ -0,0 +1,310 @@
+//! Statistics over the whole calorie inventory, not just its largest totals.
+
+use common::{cli::Command, Annotate};
+use std::{fmt, num::NonZeroUsize, path::Path, str::FromStr};
+use structopt::StructOpt;
+
+use crate::{parse, Day01, Elf, Error, Params};
+
+/// The percentiles of total calories which the report lists.
+const PERCENTILES: [u8; 7] = [0, 10, 25, 50, 75, 90, 100];
+/// The widest histogram bar, in characters.
+const BAR_WIDTH: usize = 40;
+
+/// How to write the report.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Tables of per-elf figures and percentiles, a histogram, and a list of outliers.
+    #[default]
+    Table,
+    /// The per-elf figures only, one row per elf.
+    Csv,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "table" => Ok(ReportFormat::Table),
+            "csv" => Ok(ReportFormat::Csv),
+            _ => Err(format!(
+                "unknown report format {s:?}; expected \"table\" or \"csv\""
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportFormat::Table => f.write_str("table"),
+            ReportFormat::Csv => f.write_str("csv"),
+        }
+    }
+}
+
+// The subcommands of day 1's binary.
+#[derive(Debug, StructOpt)]
+pub enum Subcommand {
+    /// summarize the whole inventory: per-elf figures, percentiles, a histogram, and outliers
+    Report {
+        /// output format: "table" or "csv"; csv lists only the per-elf figures
+        #[structopt(long, default_value)]
+        format: ReportFormat,
+
+        /// how many bins the histogram of total calories has
+        #[structopt(long, default_value = "10")]
+        bins: NonZeroUsize,
+    },
+}
+
+impl Command<Day01> for Subcommand {
+    fn run(&self, input: &Path, _params: &Params) -> color_eyre::Result<()> {
+        let Subcommand::Report { format, bins } = self;
+        let elves = parse(input).annotate()?;
+        let analysis = Analysis::new(&elves).annotate()?;
+        match format {
+            ReportFormat::Table => print!("{}", analysis.table(*bins)),
+            ReportFormat::Csv => print!("{}", analysis.csv()),
+        }
+        Ok(())
+    }
+}
+
+/// What a single elf is carrying.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ElfStats {
+    /// Elves are numbered from 1 in input order.
+    pub elf: usize,
+    pub items: usize,
+    pub total: u64,
+    pub mean: f64,
+    /// Whether the total lies outside the Tukey fences: more than 1.5 interquartile ranges beyond
+    /// the quartiles.
+    pub outlier: bool,
+}
+
+/// Statistics over every elf's inventory.
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    pub elves: Vec<ElfStats>,
+    /// Every elf's total, ascending.
+    sorted_totals: Vec<u64>,
+}
+
+impl Analysis {
+    pub fn new(elves: &[Elf]) -> Result<Self, Error> {
+        let mut stats = elves
+            .iter()
+            .enumerate()
+            .map(|(idx, elf)| {
+                let items = elf.calories.len();
+                let total = elf.total_calories();
+                ElfStats {
+                    elf: idx + 1,
+                    items,
+                    total,
+                    mean: total as f64 / items.max(1) as f64,
+                    outlier: false,
+                }
+            })
+            .collect::<Vec<_>>();
+        if stats.is_empty() {
+            return Err(Error::NoSolution);
+        }
+
+        let mut sorted_totals = stats.iter().map(|stats| stats.total).collect::<Vec<_>>();
+        sorted_totals.sort_unstable();
+        let mut analysis = Self {
+            elves: Vec::new(),
+            sorted_totals,
+        };
+
+        let (low, high) = analysis.fences();
+        for stats in &mut stats {
+            let total = stats.total as f64;
+            stats.outlier = total < low || total > high;
+        }
+        analysis.elves = stats;
+        Ok(analysis)
+    }
+
+    /// The total below which `p` percent of elves' totals lie, by the nearest-rank method.
+    pub fn percentile(&self, p: u8) -> u64 {
+        let n = self.sorted_totals.len();
+        let rank = (usize::from(p.min(100)) * n).div_ceil(100).clamp(1, n);
+        self.sorted_totals[rank - 1]
+    }
+
+    /// The `(low, high)` Tukey fences, beyond which totals are outliers.
+    fn fences(&self) -> (f64, f64) {
+        let q1 = self.percentile(25) as f64;
+        let q3 = self.percentile(75) as f64;
+        let iqr = q3 - q1;
+        (q1 - 1.5 * iqr, q3 + 1.5 * iqr)
+    }
+
+    /// Count the totals falling into `bins` equal-width bins between the smallest and largest.
+    ///
+    /// Returns `(low, high, count)` for each bin; `high` is inclusive only for the last bin.
+    pub fn histogram(&self, bins: NonZeroUsize) -> Vec<(u64, u64, usize)> {
+        let bins = bins.get();
+        let min = self.percentile(0);
+        let max = self.percentile(100);
+        let width = (max - min) as f64 / bins as f64;
+        let bound = |bin: usize| min + (width * bin as f64).round() as u64;
+
+        let mut counts = vec![0; bins];
+        for &total in &self.sorted_totals {
+            let bin = if width == 0.0 {
+                0
+            } else {
+                (((total - min) as f64 / width) as usize).min(bins - 1)
+            };
+            counts[bin] += 1;
+        }
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(bin, count)| (bound(bin), bound(bin + 1), count))
+            .collect()
+    }
+
+    /// The per-elf figures as CSV, with a header row.
+    pub fn csv(&self) -> String {
+        let mut csv = String::from("elf,items,total,mean,outlier\n");
+        for stats in &self.elves {
+            csv.push_str(&format!(
+                "{},{},{},{:.1},{}\n",
+                stats.elf, stats.items, stats.total, stats.mean, stats.outlier
+            ));
+        }
+        csv
+    }
+
+    /// Every section of the report, as human-readable tables.
+    pub fn table(&self, bins: NonZeroUsize) -> Table<'_> {
+        Table {
+            analysis: self,
+            bins,
+        }
+    }
+}
+
+/// The report as human-readable tables.
+pub struct Table<'a> {
+    analysis: &'a Analysis,
+    bins: NonZeroUsize,
+}
+
+impl fmt::Display for Table<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let analysis = self.analysis;
+
+        writeln!(
+            f,
+            "{:>5} {:>6} {:>10} {:>10}",
+            "elf", "items", "total", "mean"
+        )?;
+        for stats in &analysis.elves {
+            let marker = if stats.outlier { " *" } else { "" };
+            writeln!(
+                f,
+                "{:>5} {:>6} {:>10} {:>10.1}{marker}",
+                stats.elf, stats.items, stats.total, stats.mean
+            )?;
+        }
+
+        writeln!(f, "\npercentiles of total calories")?;
+        for p in PERCENTILES {
+            writeln!(f, "{:>5} {:>10}", format!("p{p}"), analysis.percentile(p))?;
+        }
+
+        writeln!(f, "\nhistogram of total calories")?;
+        let histogram = analysis.histogram(self.bins);
+        let most = histogram
+            .iter()
+            .map(|&(_, _, count)| count)
+            .max()
+            .unwrap_or_default()
+            .max(1);
+        for (low, high, count) in histogram {
+            let bar = "#".repeat((count * BAR_WIDTH).div_ceil(most));
+            writeln!(f, "{low:>10} - {high:<10} |{bar} {count}")?;
+        }
+
+        let (low, high) = analysis.fences();
+        writeln!(
+            f,
+            "\noutliers (marked *): totals outside {low:.1} ..= {high:.1}"
+        )?;
+        let mut outliers = analysis
+            .elves
+            .iter()
+            .filter(|stats| stats.outlier)
+            .peekable();
+        if outliers.peek().is_none() {
+            writeln!(f, "  none")?;
+        }
+        for stats in outliers {
+            writeln!(f, "  elf {}: {}", stats.elf, stats.total)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_str, EXAMPLE};
+
+    fn example() -> Analysis {
+        Analysis::new(&parse_str(EXAMPLE).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn percentiles_and_outliers() {
+        let analysis = example();
+        // totals: 4000 6000 10000 11000 24000
+        assert_eq!(analysis.percentile(0), 4000);
+        assert_eq!(analysis.percentile(25), 6000);
+        assert_eq!(analysis.percentile(50), 10000);
+        assert_eq!(analysis.percentile(75), 11000);
+        assert_eq!(analysis.percentile(100), 24000);
+
+        let outliers = analysis
+            .elves
+            .iter()
+            .filter(|stats| stats.outlier)
+            .map(|stats| stats.elf)
+            .collect::<Vec<_>>();
+        assert_eq!(outliers, [4]);
+    }
+
+    #[test]
+    fn histogram_counts_every_elf() {
+        let analysis = example();
+        let histogram = analysis.histogram(NonZeroUsize::new(4).unwrap());
+        assert_eq!(
+            histogram,
+            [
+                (4000, 9000, 2),
+                (9000, 14000, 2),
+                (14000, 19000, 0),
+                (19000, 24000, 1)
+            ]
+        );
+        assert_eq!(analysis.histogram(NonZeroUsize::MIN), [(4000, 24000, 5)]);
+    }
+
+    #[test]
+    fn csv_rows() {
+        let csv = example().csv();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("elf,items,total,mean,outlier"));
+        assert_eq!(lines.next(), Some("1,3,6000,2000.0,false"));
+        assert_eq!(lines.nth(2), Some("4,3,24000,8000.0,true"));
+        assert_eq!(csv.lines().count(), 6);
+    }
+}
@@ -0,0 +1,16 @@
+use rand::Rng;
+use std::fmt::Write;
+
+/// Generate an inventory for `size` elves, or at least three.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+    let mut input = String::new();
+    for elf in 0..size.max(3) {
+        if elf > 0 {
+            input.push('\n');
+        }
+        for _ in 0..rng.gen_range(1..=12) {
+            writeln!(input, "{}", rng.gen_range(1000..=60_000)).unwrap();
+        }
+    }
+    input
+}
@@ -1,48 +1,209 @@
-use aoclib::input::parse_newline_sep;
-use std::{path::Path, str::FromStr};
+use common::{
+    diagnostic::{lines, parse_file, Diagnosed},
+    input::{blocks, join_blocks},
+    logging, Diagnostic, Puzzle, Solution,
+};
+use rand::Rng;
+use std::{fmt, fs::File, io::Read, num::NonZeroUsize, path::Path, str::FromStr};
+use structopt::StructOpt;
 
-struct Elf {
-    calories: Vec<u32>,
+mod analytics;
+mod generate;
+mod top_k;
+
+pub use analytics::{Analysis, ElfStats, ReportFormat, Subcommand};
+pub use generate::generate;
+pub use top_k::{top_k, TopK};
+
+/// The worked example from the puzzle description.
+pub const EXAMPLE: &str = include_str!("../example.txt");
+
+/// How many elves part 2 totals, as the puzzle asks.
+pub const TOP_K: NonZeroUsize = NonZeroUsize::new(3).unwrap();
+
+// How many elves to total.
+#[derive(Debug, Clone, PartialEq, Eq, StructOpt)]
+pub struct Params {
+    /// how many of the elves carrying the most calories to total (part 2)
+    #[structopt(long, default_value = "3")]
+    pub top_k: NonZeroUsize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self { top_k: TOP_K }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Elf {
+    calories: Vec<u64>,
+    total: u64,
 }
 
 impl FromStr for Elf {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let calories = s
-            .lines()
-            .map(|line| line.trim())
-            .filter(|line| !line.is_empty())
-            .map(|line| line.parse::<u32>())
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|_| Error::MalformedInput)?;
+        Self::parse_in(1, s, s)
+    }
+}
 
-        Ok(Self { calories })
+impl fmt::Display for Elf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for calories in &self.calories {
+            writeln!(f, "{calories}")?;
+        }
+        Ok(())
     }
 }
 
 impl Elf {
-    fn total_calories(&self) -> u32 {
-        self.calories.iter().sum()
+    /// Parse the block of `input` belonging to elf number `elf`.
+    fn parse_in(elf: usize, input: &str, block: &str) -> Result<Self, Error> {
+        let mut calories = Vec::new();
+        let mut total = 0;
+        for line in lines(block) {
+            let (count, new_total) = add_calories(elf, total, input, line)?;
+            calories.push(count);
+            total = new_total;
+        }
+
+        Ok(Self { calories, total })
+    }
+
+    fn total_calories(&self) -> u64 {
+        self.total
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let elves: Vec<Elf> = parse_newline_sep(input)?.collect();
-    let careful_elf = elves
-        .iter()
-        .max_by_key(|elf| elf.total_calories())
-        .ok_or(Error::NoSolution)?;
-    println!("most calories: {}", careful_elf.total_calories());
-    Ok(())
+/// Parse the calorie count `token` and add it to elf number `elf`'s running `total`, returning
+/// the count and the new total.
+///
+/// `token` must be a slice of `input`, so that errors can point at it.
+fn add_calories(elf: usize, total: u64, input: &str, token: &str) -> Result<(u64, u64), Error> {
+    let count = token
+        .parse::<u64>()
+        .map_err(|err| Error::MalformedCalories {
+            elf,
+            token: token.into(),
+            diagnostic: Diagnostic::new(input, token, format!("malformed calorie count: {err}")),
+        })?;
+    let total = total
+        .checked_add(count)
+        .ok_or_else(|| Error::CalorieOverflow {
+            elf,
+            diagnostic: Diagnostic::new(input, token, "total calories overflow a u64"),
+        })?;
+    Ok((count, total))
+}
+
+pub fn parse_str(input: &str) -> Result<Vec<Elf>, Error> {
+    blocks(input)
+        .enumerate()
+        .map(|(idx, block)| Elf::parse_in(idx + 1, input, block))
+        .collect()
+}
+
+pub fn from_reader(mut reader: impl Read) -> Result<Vec<Elf>, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_str(&input)
+}
+
+pub fn parse(input: &Path) -> Result<Vec<Elf>, Error> {
+    parse_file(input, parse_str)
+}
+
+/// Select the `k` elves carrying the most calories from those already parsed.
+fn select(elves: &[Elf], k: usize) -> TopK {
+    let mut selector = top_k::Selector::new(k);
+    for elf in elves {
+        selector.push(elf.total_calories());
+    }
+    selector.finish()
+}
+
+/// Total the calories carried by the selected elves, listing them.
+fn solution(top: &TopK) -> Result<Solution, Error> {
+    if top.elves.is_empty() {
+        return Err(Error::NoSolution);
+    }
+    Ok(top
+        .describe()
+        .into_iter()
+        .fold(Solution::new(top.total()?), Solution::with_extra))
+}
+
+pub fn part1(elves: &[Elf]) -> Result<Solution, Error> {
+    solution(&select(elves, 1))
+}
+
+pub fn part2(elves: &[Elf]) -> Result<Solution, Error> {
+    part2_with(elves, &Params::default())
+}
+
+pub fn part2_with(elves: &[Elf], params: &Params) -> Result<Solution, Error> {
+    solution(&select(elves, params.top_k.get()))
+}
+
+/// Solve part 1, streaming the input instead of reading it all into memory.
+pub fn solve_part1(input: &Path) -> Result<Solution, Error> {
+    solution(&top_k(File::open(input)?, 1)?)
+}
+
+pub fn solve_part2(input: &Path) -> Result<Solution, Error> {
+    solve_part2_with(input, &Params::default())
+}
+
+/// Solve part 2, streaming the input instead of reading it all into memory.
+pub fn solve_part2_with(input: &Path, params: &Params) -> Result<Solution, Error> {
+    solution(&top_k(File::open(input)?, params.top_k.get())?)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let mut elves: Vec<Elf> = parse_newline_sep(input)?.collect();
-    elves.sort_by_cached_key(|elf| std::cmp::Reverse(elf.total_calories()));
-    let top_3_total_calories: u32 = elves.iter().take(3).map(|elf| elf.total_calories()).sum();
-    println!("top 3 calories: {top_3_total_calories}");
-    Ok(())
+/// Day 1: Calorie Counting
+pub struct Day01;
+
+impl Puzzle for Day01 {
+    const DAY: u8 = 1;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const LABELS: [&'static str; 2] = ["most calories", "calories of the top elves"];
+
+    type Input = Vec<Elf>;
+    type Params = Params;
+    type Answer1 = Solution;
+    type Answer2 = Solution;
+    type Error = Error;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_str(input)
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Solution, Error> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input, params: &Params) -> Result<Solution, Error> {
+        part2_with(input, params)
+    }
+
+    /// Streams the input, rather than reading it all into memory.
+    fn solve_file(path: &Path, part: u8, params: &Params) -> Result<Solution, Error> {
+        let _span = logging::part_span(Self::DAY, part).entered();
+        match part {
+            1 => solve_part1(path),
+            2 => solve_part2_with(path, params),
+            _ => Self::solve(&Self::parse_file(path)?, part, params),
+        }
+    }
+
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn render(input: &Self::Input) -> String {
+        join_blocks(input)
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -51,6 +212,85 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("no solution found")]
     NoSolution,
-    #[error("malformed calorie input")]
-    MalformedInput,
+    #[error("elf {elf}, line {}: malformed calorie count {token:?}", .diagnostic.line)]
+    MalformedCalories {
+        elf: usize,
+        token: Box<str>,
+        #[source]
+        diagnostic: Diagnostic,
+    },
+    #[error("elf {elf}, line {}: total calories overflow", .diagnostic.line)]
+    CalorieOverflow {
+        elf: usize,
+        #[source]
+        diagnostic: Diagnostic,
+    },
+    #[error("the selected elves' total calories overflow")]
+    TotalOverflow,
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::MalformedCalories { diagnostic, .. }
+            | Error::CalorieOverflow { diagnostic, .. } => Some(diagnostic),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::Answer;
+
+    #[test]
+    fn example_part1() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part1(&input).unwrap().answer, Answer::from(24000));
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part2(&input).unwrap().answer, Answer::from(45000));
+    }
+
+    #[test]
+    fn streaming_matches_parsing() {
+        for seed in 0..8 {
+            let input = common::puzzle::generate_seeded::<Day01>(seed, 50);
+            let elves = parse_str(&input).unwrap();
+            for k in [1, 3, 10] {
+                assert_eq!(top_k(input.as_bytes(), k).unwrap(), select(&elves, k));
+            }
+        }
+    }
+
+    #[test]
+    fn errors_name_elf_line_and_token() {
+        let err = parse_str("1000\n\n2000\n 3x00\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "elf 2, line 4: malformed calorie count \"3x00\""
+        );
+        let Error::MalformedCalories { diagnostic, .. } = err else {
+            panic!("{err:?}");
+        };
+        assert_eq!(diagnostic.column, 2);
+
+        let err = parse_str(&format!("1\n\n{}\n1\n", u64::MAX)).unwrap_err();
+        assert_eq!(err.to_string(), "elf 2, line 4: total calories overflow");
+    }
+
+    #[test]
+    fn cli_defaults_match_constants() {
+        assert_eq!(Params::from_iter(["day01"]), Params::default());
+        assert!(Params::from_iter_safe(["day01", "--top-k", "0"]).is_err());
+    }
+
+    #[test]
+    fn render_round_trips() {
+        common::puzzle::assert_round_trips::<Day01>(0..8, 20);
+    }
 }
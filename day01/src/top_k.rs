@@ -0,0 +1,228 @@
+//! Find the elves carrying the most calories without holding every elf in memory.
+
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    io::{BufRead, BufReader, Read},
+};
+
+use crate::{add_calories, Error};
+use common::diagnostic::Diagnosed;
+
+/// The `k` elves carrying the most calories.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopK {
+    /// `(elf number, total calories)`, most calories first. Elves are numbered from 1 in input order;
+    /// among elves carrying the same total, earlier elves are preferred.
+    pub elves: Vec<(usize, u64)>,
+    /// How many elves were left out despite carrying as many calories as the last of `elves`.
+    pub tied_out: usize,
+}
+
+impl TopK {
+    /// The calories carried by all of these elves together.
+    pub fn total(&self) -> Result<u64, Error> {
+        self.elves
+            .iter()
+            .try_fold(0_u64, |sum, &(_, total)| sum.checked_add(total))
+            .ok_or(Error::TotalOverflow)
+    }
+
+    /// Describe the selection: one line per elf, then one for any ties which were left out.
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = self
+            .elves
+            .iter()
+            .map(|(elf, total)| format!("elf {elf}: {total}"))
+            .collect::<Vec<_>>();
+        if let (Some((_, last)), n @ 1..) = (self.elves.last(), self.tied_out) {
+            let (elves, were) = if n == 1 {
+                ("elf", "was")
+            } else {
+                ("elves", "were")
+            };
+            lines.push(format!(
+                "{n} more {elves} also carried {last} calories but {were} left out"
+            ));
+        }
+        lines
+    }
+}
+
+/// Keeps the `k` highest totals seen so far in a bounded min-heap.
+pub struct Selector {
+    k: usize,
+    /// Ordered so that the heap's top is the first elf to be displaced: the lowest total, and
+    /// among equal totals, the latest elf.
+    heap: BinaryHeap<Reverse<(u64, Reverse<usize>)>>,
+    tied_out: usize,
+    elves: usize,
+}
+
+impl Selector {
+    pub fn new(k: usize) -> Self {
+        Self {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+            tied_out: 0,
+            elves: 0,
+        }
+    }
+
+    /// The lowest total currently kept.
+    fn threshold(&self) -> Option<u64> {
+        self.heap.peek().map(|Reverse((total, _))| *total)
+    }
+
+    /// Consider the next elf's total.
+    pub fn push(&mut self, total: u64) {
+        self.elves += 1;
+        let elf = self.elves;
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse((total, Reverse(elf))));
+            return;
+        }
+        let Some(threshold) = self.threshold() else {
+            // k is 0
+            return;
+        };
+
+        if total == threshold {
+            // later elves lose ties
+            self.tied_out += 1;
+        } else if total > threshold {
+            self.heap.push(Reverse((total, Reverse(elf))));
+            let Reverse((displaced, _)) = self.heap.pop().expect("heap is not empty");
+            if Some(displaced) == self.threshold() {
+                self.tied_out += 1;
+            } else {
+                // every elf tied at the old threshold is now below the new one
+                self.tied_out = 0;
+            }
+        }
+    }
+
+    pub fn finish(self) -> TopK {
+        let mut elves = self
+            .heap
+            .into_iter()
+            .map(|Reverse((total, Reverse(elf)))| (elf, total))
+            .collect::<Vec<_>>();
+        elves.sort_unstable_by_key(|&(elf, total)| (Reverse(total), elf));
+        TopK {
+            elves,
+            tied_out: self.tied_out,
+        }
+    }
+}
+
+/// Find the `k` elves carrying the most calories in an input, reading it a line at a time.
+///
+/// Memory use is bounded by `k` and the longest line, not by the size of the input.
+pub fn top_k(reader: impl Read, k: usize) -> Result<TopK, Error> {
+    let mut reader = BufReader::new(reader);
+    let mut selector = Selector::new(k);
+    let mut line = String::new();
+    let mut line_number = 0;
+    let mut elves = 0;
+    // the current elf's total, once it has at least one line
+    let mut elf = None;
+
+    loop {
+        line.clear();
+        let eof = reader.read_line(&mut line)? == 0;
+        line_number += 1;
+        let trimmed = line.trim();
+
+        if eof || trimmed.is_empty() {
+            if let Some(total) = elf.take() {
+                selector.push(total);
+            }
+            if eof {
+                break;
+            }
+            continue;
+        }
+
+        if elf.is_none() {
+            elves += 1;
+        }
+        let (_, total) =
+            add_calories(elves, elf.unwrap_or(0), &line, trimmed).map_err(|mut err| {
+                // the diagnostic only saw this line
+                if let Some(diagnostic) = err.diagnostic_mut() {
+                    diagnostic.line = line_number;
+                }
+                err
+            })?;
+        elf = Some(total);
+    }
+
+    Ok(selector.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn select(totals: &[u64], k: usize) -> TopK {
+        let mut selector = Selector::new(k);
+        for &total in totals {
+            selector.push(total);
+        }
+        selector.finish()
+    }
+
+    #[test]
+    fn reports_ties() {
+        let top = select(&[5, 9, 7, 7, 3, 7], 2);
+        assert_eq!(top.elves, [(2, 9), (3, 7)]);
+        assert_eq!(top.tied_out, 2);
+        assert_eq!(
+            top.describe().last().unwrap(),
+            "2 more elves also carried 7 calories but were left out"
+        );
+
+        // a higher total displaces a tie, and the remaining ties are no longer tied
+        let top = select(&[5, 9, 7, 7, 8], 2);
+        assert_eq!(top.elves, [(2, 9), (5, 8)]);
+        assert_eq!(top.tied_out, 0);
+
+        // displacing one of several tied elves leaves the rest tied
+        let top = select(&[7, 7, 7, 9], 2);
+        assert_eq!(top.elves, [(4, 9), (1, 7)]);
+        assert_eq!(top.tied_out, 2);
+    }
+
+    #[test]
+    fn fewer_elves_than_k() {
+        assert_eq!(select(&[4, 6], 3).elves, [(2, 6), (1, 4)]);
+        assert_eq!(select(&[4, 6], 0).elves, []);
+    }
+
+    #[test]
+    fn streams_the_example() {
+        let top = top_k(crate::EXAMPLE.as_bytes(), 3).unwrap();
+        assert_eq!(top.elves, [(4, 24000), (3, 11000), (5, 10000)]);
+        assert_eq!(top.total().unwrap(), 45000);
+    }
+
+    #[test]
+    fn reports_malformed_line_numbers() {
+        let err = top_k("1000\n\n2000\nlots\n".as_bytes(), 3).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "elf 2, line 4: malformed calorie count \"lots\""
+        );
+
+        let input = format!("1\n\n\n{}\n1\n", u64::MAX);
+        let err = top_k(input.as_bytes(), 3).unwrap_err();
+        assert_eq!(err.to_string(), "elf 2, line 5: total calories overflow");
+    }
+
+    #[test]
+    fn totals_overflow() {
+        let top = select(&[u64::MAX, 1], 2);
+        assert!(matches!(top.total(), Err(Error::TotalOverflow)));
+    }
+}
@@ -0,0 +1,242 @@
+//! What if the elf meant something else by the response column?
+//!
+//! Score a guide under every reading of its response sigils: each assignment of them to moves, and
+//! each assignment of them to outcomes, including those in which several sigils mean the same.
+
+use common::{cli::Command, Annotate};
+use std::{fmt, path::Path};
+use structopt::StructOpt;
+
+use crate::{parse_with, Day02, Error, Game, Instruction, Params};
+
+/// Guides for games with more moves than this have too many interpretations to score them all.
+///
+/// A game of `n` moves has `2 * n^n` interpretations: 6250 for a game of 5.
+pub const MAX_MOVES: usize = 5;
+
+/// What the response column names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Meaning {
+    Moves,
+    Outcomes,
+}
+
+impl fmt::Display for Meaning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Meaning::Moves => f.pad("moves"),
+            Meaning::Outcomes => f.pad("outcomes"),
+        }
+    }
+}
+
+/// One reading of the response column, and the score the guide earns under it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interpretation {
+    pub meaning: Meaning,
+    /// `assignment[r]` is what the `r`th response sigil stands for: a move, or an outcome as
+    /// counted by [`Game::move_for_outcome`].
+    pub assignment: Vec<usize>,
+    pub score: u64,
+}
+
+impl Interpretation {
+    /// Whether this is how one of the puzzle's parts reads the guide.
+    pub fn is_puzzle_reading(&self) -> bool {
+        self.assignment.iter().enumerate().all(|(r, &a)| r == a)
+    }
+
+    /// List what each response sigil stands for, like `X=rock Y=paper Z=scissors`.
+    pub fn describe(&self, game: &Game) -> String {
+        let n = game.moves().len();
+        game.response_sigils()
+            .iter()
+            .zip(&self.assignment)
+            .map(|(sigil, &a)| {
+                let meaning = match self.meaning {
+                    Meaning::Moves => game.moves()[a].clone(),
+                    Meaning::Outcomes => outcome_name(a, n),
+                };
+                format!("{sigil}={meaning}")
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Name the outcome at `index` among `n`: `lose`, `draw`, or `win`, and for games of more than
+/// three moves, how many places around the cycle the chosen move lies from the opponent's.
+fn outcome_name(index: usize, n: usize) -> String {
+    let (name, distance) = match index.cmp(&(n / 2)) {
+        std::cmp::Ordering::Less => ("lose", n / 2 - index),
+        std::cmp::Ordering::Equal => return "draw".into(),
+        std::cmp::Ordering::Greater => ("win", index - n / 2),
+    };
+    if n == 3 {
+        name.into()
+    } else {
+        format!("{name}-by-{distance}")
+    }
+}
+
+/// Every assignment of `n` sigils to `0..n`, repeats allowed, in lexicographic order.
+fn assignments(n: usize) -> Vec<Vec<usize>> {
+    fn extend(n: usize, prefix: &mut Vec<usize>, assignments: &mut Vec<Vec<usize>>) {
+        if prefix.len() == n {
+            assignments.push(prefix.clone());
+            return;
+        }
+        for next in 0..n {
+            prefix.push(next);
+            extend(n, prefix, assignments);
+            prefix.pop();
+        }
+    }
+
+    let mut assignments = Vec::new();
+    extend(n, &mut Vec::with_capacity(n), &mut assignments);
+    assignments
+}
+
+/// Score `instructions` under every interpretation of the response column, best first.
+///
+/// Among equal scores, moves come before outcomes, then assignments in lexicographic order.
+pub fn explore(game: &Game, instructions: &[Instruction]) -> Result<Vec<Interpretation>, Error> {
+    let n = game.moves().len();
+    if n > MAX_MOVES {
+        return Err(Error::TooManyMoves { moves: n });
+    }
+
+    // every interpretation treats the same pair of sigils alike, so count each pair once
+    let mut counts = vec![vec![0_u64; n]; n];
+    for instruction in instructions {
+        let opponent = game.opponent_move(instruction.opponent)?;
+        let response = game.response_index(instruction.response)?;
+        counts[opponent][response] += 1;
+    }
+
+    let mut interpretations = Vec::new();
+    for meaning in [Meaning::Moves, Meaning::Outcomes] {
+        for assignment in assignments(n) {
+            let mut score = 0_u64;
+            for (opponent, row) in counts.iter().enumerate() {
+                for (&count, &a) in row.iter().zip(&assignment) {
+                    let me = match meaning {
+                        Meaning::Moves => a,
+                        Meaning::Outcomes => game.move_for_outcome(opponent, a),
+                    };
+                    score = count
+                        .checked_mul(game.score(me, opponent))
+                        .and_then(|points| score.checked_add(points))
+                        .ok_or(Error::ScoreOverflow)?;
+                }
+            }
+            interpretations.push(Interpretation {
+                meaning,
+                assignment,
+                score,
+            });
+        }
+    }
+    // the sort is stable, so ties keep the order they were generated in
+    interpretations.sort_by_key(|interpretation| std::cmp::Reverse(interpretation.score));
+    Ok(interpretations)
+}
+
+// The subcommands of day 2's binary.
+#[derive(Debug, StructOpt)]
+pub enum Subcommand {
+    /// score the guide under every reading of its response column, as moves or as outcomes,
+    /// reporting the best and worst
+    Explore,
+}
+
+impl Command<Day02> for Subcommand {
+    fn run(&self, input: &Path, params: &Params) -> color_eyre::Result<()> {
+        let Subcommand::Explore = self;
+        let game = params.game();
+        let guide = parse_with(input, &game).annotate()?;
+        let interpretations = explore(&game, &guide).annotate()?;
+
+        let row = |interpretation: &Interpretation| {
+            let marker = if interpretation.is_puzzle_reading() {
+                " (puzzle)"
+            } else {
+                ""
+            };
+            format!(
+                "{:>8}  {:<8}  {}{marker}",
+                interpretation.score,
+                interpretation.meaning,
+                interpretation.describe(&game)
+            )
+        };
+        println!("{:>8}  {:<8}  meaning", "score", "names");
+        for interpretation in &interpretations {
+            println!("{}", row(interpretation));
+        }
+
+        let (Some(best), Some(worst)) = (interpretations.first(), interpretations.last()) else {
+            return Ok(());
+        };
+        for (label, score) in [("best", best.score), ("worst", worst.score)] {
+            println!("\n{label}:");
+            for interpretation in interpretations.iter().filter(|i| i.score == score) {
+                println!("{}", row(interpretation));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_str, EXAMPLE};
+
+    #[test]
+    fn finds_best_and_worst_readings() {
+        let game = Game::default();
+        let interpretations = explore(&game, &parse_str(EXAMPLE).unwrap()).unwrap();
+        assert_eq!(interpretations.len(), 54);
+
+        let best = interpretations.first().unwrap();
+        assert_eq!((best.meaning, best.score), (Meaning::Moves, 24));
+        assert_eq!(best.describe(&game), "X=scissors Y=paper Z=rock");
+        let worst = interpretations.last().unwrap();
+        assert_eq!((worst.meaning, worst.score), (Meaning::Outcomes, 6));
+        assert_eq!(worst.describe(&game), "X=lose Y=lose Z=lose");
+
+        let puzzle = interpretations
+            .iter()
+            .filter(|interpretation| interpretation.is_puzzle_reading())
+            .map(|interpretation| (interpretation.meaning, interpretation.score))
+            .collect::<Vec<_>>();
+        assert_eq!(puzzle, [(Meaning::Moves, 15), (Meaning::Outcomes, 12)]);
+    }
+
+    #[test]
+    fn scores_readings_which_repeat_a_meaning() {
+        let game = Game::default();
+        let interpretations = explore(&game, &parse_str(EXAMPLE).unwrap()).unwrap();
+        let all_rock = interpretations
+            .iter()
+            .find(|interpretation| {
+                interpretation.meaning == Meaning::Moves && interpretation.assignment == [0, 0, 0]
+            })
+            .unwrap();
+        assert_eq!(all_rock.describe(&game), "X=rock Y=rock Z=rock");
+        // a draw against rock, a loss to paper, and a win against scissors
+        assert_eq!(all_rock.score, 4 + 1 + 7);
+    }
+
+    #[test]
+    fn names_outcomes() {
+        let names = |n| (0..n).map(|idx| outcome_name(idx, n)).collect::<Vec<_>>();
+        assert_eq!(names(3), ["lose", "draw", "win"]);
+        assert_eq!(
+            names(5),
+            ["lose-by-2", "lose-by-1", "draw", "win-by-1", "win-by-2"]
+        );
+    }
+}
@@ -0,0 +1,323 @@
+//! Games of cyclic dominance: Rock Paper Scissors, and its variants with more moves.
+//!
+//! A game lists an odd number of moves in a cycle. Each move beats the half of the other moves
+//! which come just before it, wrapping around, and loses to the half which come just after it.
+//! Rock Paper Scissors lists its moves as rock, paper, scissors; Rock Paper Scissors Lizard Spock
+//! lists them as rock, spock, paper, lizard, scissors.
+
+use common::{diagnostic::lines, Diagnostic};
+use serde::Deserialize;
+use std::{collections::HashSet, path::Path};
+
+use crate::{Error, Instruction};
+
+/// Who won a round, from the player's point of view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+/// Points for each outcome of a round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutcomeScores {
+    pub lose: u32,
+    pub draw: u32,
+    pub win: u32,
+}
+
+impl Default for OutcomeScores {
+    fn default() -> Self {
+        Self {
+            lose: 0,
+            draw: 3,
+            win: 6,
+        }
+    }
+}
+
+/// A game definition as written in a TOML file, before validation.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Definition {
+    moves: Vec<String>,
+    /// Defaults to 1, 2, 3, ... in the order of `moves`.
+    move_scores: Option<Vec<u32>>,
+    #[serde(default)]
+    outcome_scores: OutcomeScores,
+    /// Defaults to the first letters of the alphabet.
+    opponent_sigils: Option<String>,
+    /// Defaults to the last letters of the alphabet.
+    response_sigils: Option<String>,
+}
+
+/// The rules of a game, and the sigils which a strategy guide uses for it.
+///
+/// The opponent's column of the guide always names a move. The response column names a move in
+/// the first interpretation, and an outcome in the second: its first half of sigils lose, its middle
+/// sigil draws, and its last half win, each by choosing the move that many places around the cycle
+/// from the opponent's.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "Definition")]
+pub struct Game {
+    moves: Vec<String>,
+    move_scores: Vec<u32>,
+    outcome_scores: OutcomeScores,
+    opponent_sigils: Vec<char>,
+    response_sigils: Vec<char>,
+}
+
+/// Check that `sigils` gives each of `n` moves its own visible char.
+fn check_sigils(column: &str, sigils: &[char], n: usize) -> Result<(), String> {
+    if sigils.len() != n {
+        return Err(format!(
+            "{column} has {} sigils for {n} moves",
+            sigils.len()
+        ));
+    }
+    if let Some(sigil) = sigils.iter().find(|sigil| sigil.is_whitespace()) {
+        return Err(format!("{column} contains whitespace {sigil:?}"));
+    }
+    if sigils.iter().collect::<HashSet<_>>().len() != n {
+        return Err(format!("{column} repeats a sigil"));
+    }
+    Ok(())
+}
+
+impl TryFrom<Definition> for Game {
+    type Error = String;
+
+    fn try_from(definition: Definition) -> Result<Self, Self::Error> {
+        let n = definition.moves.len();
+        if n < 3 || n.is_multiple_of(2) {
+            return Err(format!(
+                "a cyclic game needs an odd number of moves, at least 3; got {n}"
+            ));
+        }
+        if definition.moves.iter().collect::<HashSet<_>>().len() != n {
+            return Err("moves repeats a move".into());
+        }
+
+        let move_scores = definition
+            .move_scores
+            .unwrap_or_else(|| (1..).take(n).collect());
+        if move_scores.len() != n {
+            return Err(format!(
+                "move_scores has {} scores for {n} moves",
+                move_scores.len()
+            ));
+        }
+
+        let alphabet = ('A'..='Z').collect::<Vec<_>>();
+        let default_sigils = |letters: &[char]| {
+            if n > letters.len() {
+                Err(format!(
+                    "{n} moves are too many for default sigils; set opponent_sigils and response_sigils"
+                ))
+            } else {
+                Ok(letters.to_vec())
+            }
+        };
+        let opponent_sigils = match definition.opponent_sigils {
+            Some(sigils) => sigils.chars().collect(),
+            None => default_sigils(&alphabet[..n.min(alphabet.len())])?,
+        };
+        let response_sigils = match definition.response_sigils {
+            Some(sigils) => sigils.chars().collect(),
+            None => default_sigils(&alphabet[alphabet.len().saturating_sub(n)..])?,
+        };
+        check_sigils("opponent_sigils", &opponent_sigils, n)?;
+        check_sigils("response_sigils", &response_sigils, n)?;
+
+        Ok(Self {
+            moves: definition.moves,
+            move_scores,
+            outcome_scores: definition.outcome_scores,
+            opponent_sigils,
+            response_sigils,
+        })
+    }
+}
+
+impl Default for Game {
+    /// Rock Paper Scissors, as the puzzle describes it.
+    fn default() -> Self {
+        Definition {
+            moves: vec!["rock".into(), "paper".into(), "scissors".into()],
+            move_scores: None,
+            outcome_scores: OutcomeScores::default(),
+            opponent_sigils: None,
+            response_sigils: None,
+        }
+        .try_into()
+        .expect("rock paper scissors is a valid game")
+    }
+}
+
+impl Game {
+    /// Read a game definition from TOML.
+    pub fn from_toml(toml: &str) -> Result<Self, Error> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    /// Read a game definition from a TOML file.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    /// The names of the moves, in cycle order.
+    pub fn moves(&self) -> &[String] {
+        &self.moves
+    }
+
+    /// The sigils of the response column, in the order of the moves and outcomes they stand for.
+    pub fn response_sigils(&self) -> &[char] {
+        &self.response_sigils
+    }
+
+    /// The move which the opponent's `sigil` names.
+    pub(crate) fn opponent_move(&self, sigil: char) -> Result<usize, Error> {
+        self.opponent_sigils
+            .iter()
+            .position(|&known| known == sigil)
+            .ok_or(Error::UnknownSigil { sigil })
+    }
+
+    /// The position of the response `sigil` among the response sigils.
+    pub(crate) fn response_index(&self, sigil: char) -> Result<usize, Error> {
+        self.response_sigils
+            .iter()
+            .position(|&known| known == sigil)
+            .ok_or(Error::UnknownSigil { sigil })
+    }
+
+    /// The outcome of playing `me` against `opponent`.
+    pub fn outcome(&self, me: usize, opponent: usize) -> Outcome {
+        let n = self.moves.len();
+        match (me + n - opponent) % n {
+            0 => Outcome::Draw,
+            distance if distance <= n / 2 => Outcome::Win,
+            _ => Outcome::Lose,
+        }
+    }
+
+    /// The move which the response at `index` calls for against `opponent`, when responses name
+    /// outcomes.
+    pub fn move_for_outcome(&self, opponent: usize, index: usize) -> usize {
+        let n = self.moves.len();
+        (opponent + n + index - n / 2) % n
+    }
+
+    /// Points for playing `me` against `opponent`.
+    ///
+    /// Scores are `u32`, so their sum can't overflow a `u64`.
+    pub fn score(&self, me: usize, opponent: usize) -> u64 {
+        let outcome = match self.outcome(me, opponent) {
+            Outcome::Lose => self.outcome_scores.lose,
+            Outcome::Draw => self.outcome_scores.draw,
+            Outcome::Win => self.outcome_scores.win,
+        };
+        u64::from(outcome) + u64::from(self.move_scores[me])
+    }
+
+    /// Parse a strategy guide written with this game's sigils.
+    pub fn parse_guide(&self, input: &str) -> Result<Vec<Instruction>, Error> {
+        Ok(lines(input)
+            .map(|line| {
+                let instruction = line.parse::<Instruction>().map_err(|err| {
+                    Diagnostic::new(input, line, format!("malformed instruction: {err}"))
+                })?;
+                self.opponent_move(instruction.opponent)
+                    .and(self.response_index(instruction.response))
+                    .map_err(|err| Diagnostic::new(input, line, err))?;
+                Ok(instruction)
+            })
+            .collect::<Result<_, Diagnostic>>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{part1_with, part2_with};
+    use common::Answer;
+
+    const RPSLS: &str = include_str!("../rpsls.toml");
+
+    #[test]
+    fn every_move_beats_half_the_others() {
+        for game in [Game::default(), Game::from_toml(RPSLS).unwrap()] {
+            let n = game.moves().len();
+            for me in 0..n {
+                let wins = (0..n)
+                    .filter(|&opponent| game.outcome(me, opponent) == Outcome::Win)
+                    .count();
+                assert_eq!(wins, n / 2, "{}", game.moves()[me]);
+                for opponent in 0..n {
+                    let mirrored = match game.outcome(opponent, me) {
+                        Outcome::Lose => Outcome::Win,
+                        Outcome::Draw => Outcome::Draw,
+                        Outcome::Win => Outcome::Lose,
+                    };
+                    assert_eq!(game.outcome(me, opponent), mirrored);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rpsls_guide() {
+        let game = Game::from_toml(RPSLS).unwrap();
+        assert_eq!(game.response_sigils(), ['V', 'W', 'X', 'Y', 'Z']);
+        let guide = game.parse_guide("A Z\nE V\nB Y\n").unwrap();
+        assert_eq!(part1_with(&guide, &game).unwrap().answer, Answer::from(20));
+        assert_eq!(part2_with(&guide, &game).unwrap().answer, Answer::from(18));
+
+        let err = Game::default().parse_guide("A Z\nE V\n").unwrap_err();
+        let Error::Parse(diagnostic) = err else {
+            panic!("{err:?}");
+        };
+        assert_eq!(diagnostic.line, 2);
+    }
+
+    #[test]
+    fn large_scores_do_not_overflow() {
+        let game = Game::from_toml(&format!(
+            r#"moves = ["rock", "paper", "scissors"]
+            move_scores = [{max}, {max}, {max}]
+            outcome_scores = {{ lose = {max}, draw = {max}, win = {max} }}"#,
+            max = u32::MAX
+        ))
+        .unwrap();
+        let guide = game.parse_guide("A X\nB Y\n").unwrap();
+        assert_eq!(
+            part1_with(&guide, &game).unwrap().answer,
+            Answer::from(4 * u64::from(u32::MAX))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_definitions() {
+        for (toml, message) in [
+            (
+                r#"moves = ["rock", "paper", "scissors", "well"]"#,
+                "odd number of moves",
+            ),
+            (
+                r#"moves = ["rock", "paper", "scissors"]
+                move_scores = [1, 2]"#,
+                "2 scores for 3 moves",
+            ),
+            (
+                r#"moves = ["rock", "paper", "scissors"]
+                response_sigils = "XXZ""#,
+                "repeats a sigil",
+            ),
+        ] {
+            let err = Game::from_toml(toml).unwrap_err().to_string();
+            assert!(err.contains(message), "{err}");
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use rand::Rng;
+use std::fmt::Write;
+
+/// Generate a strategy guide of `size` rounds.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+    let mut input = String::new();
+    for _ in 0..size {
+        let opponent = ['A', 'B', 'C'][rng.gen_range(0..3)];
+        let response = ['X', 'Y', 'Z'][rng.gen_range(0..3)];
+        writeln!(input, "{opponent} {response}").unwrap();
+    }
+    input
+}
@@ -1,153 +1,199 @@
-use aoclib::parse;
+use common::{
+    diagnostic::{parse_file, Diagnosed},
+    input::join_lines,
+    logging, Diagnostic, Puzzle, Solution,
+};
 use parse_display::{Display, FromStr};
-use std::path::Path;
+use rand::Rng;
+use std::{borrow::Cow, io::Read, path::Path};
+use structopt::StructOpt;
+
+mod explore;
+mod game;
+mod generate;
+
+pub use explore::{explore, Interpretation, Meaning, Subcommand};
+pub use game::{Game, Outcome, OutcomeScores};
+pub use generate::generate;
+
+/// The worked example from the puzzle description.
+pub const EXAMPLE: &str = include_str!("../example.txt");
+
+// Which game the guide is written for.
+#[derive(Debug, Default, Clone, PartialEq, Eq, StructOpt)]
+pub struct Params {
+    /// a TOML file defining the game to play instead of Rock Paper Scissors
+    #[structopt(long, parse(try_from_str = load_game))]
+    pub game: Option<Game>,
+}
 
-/// Input column 1
-#[derive(Debug, Clone, Copy, PartialEq, Eq, FromStr, Display)]
-enum OpponentSigil {
-    A,
-    B,
-    C,
+impl Params {
+    /// The game to play: the one given with `--game`, or Rock Paper Scissors.
+    pub fn game(&self) -> Cow<'_, Game> {
+        match &self.game {
+            Some(game) => Cow::Borrowed(game),
+            None => Cow::Owned(Game::default()),
+        }
+    }
 }
 
-/// Input column 2
-#[derive(Debug, Clone, Copy, PartialEq, Eq, FromStr, Display)]
-enum OtherSigil {
-    X,
-    Y,
-    Z,
+fn load_game(path: &str) -> Result<Game, Error> {
+    Game::load(Path::new(path))
 }
 
+/// One line of a strategy guide: the opponent's sigil, then the response sigil.
+///
+/// What the sigils mean depends on the [`Game`] and on which interpretation of the guide is in use.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, FromStr, Display)]
-#[display("{opponent} {other}")]
-struct Instruction {
-    opponent: OpponentSigil,
-    other: OtherSigil,
+#[display("{opponent} {response}")]
+pub struct Instruction {
+    opponent: char,
+    response: char,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Rps {
-    Rock,
-    Paper,
-    Scissors,
-}
-
-impl From<OpponentSigil> for Rps {
-    fn from(sigil: OpponentSigil) -> Self {
-        match sigil {
-            OpponentSigil::A => Rps::Rock,
-            OpponentSigil::B => Rps::Paper,
-            OpponentSigil::C => Rps::Scissors,
-        }
-    }
+struct Round {
+    opponent: usize,
+    me: usize,
 }
 
-impl From<OtherSigil> for Rps {
-    fn from(sigil: OtherSigil) -> Self {
-        match sigil {
-            OtherSigil::X => Rps::Rock,
-            OtherSigil::Y => Rps::Paper,
-            OtherSigil::Z => Rps::Scissors,
-        }
+impl Round {
+    /// The response names my move.
+    fn from_instruction_pt1(
+        game: &Game,
+        Instruction { opponent, response }: Instruction,
+    ) -> Result<Self, Error> {
+        let opponent = game.opponent_move(opponent)?;
+        let me = game.response_index(response)?;
+        Ok(Self { opponent, me })
     }
-}
 
-impl Rps {
-    const fn score(self) -> u32 {
-        match self {
-            Rps::Rock => 1,
-            Rps::Paper => 2,
-            Rps::Scissors => 3,
-        }
+    /// The response names the outcome I need.
+    fn from_instruction_pt2(
+        game: &Game,
+        Instruction { opponent, response }: Instruction,
+    ) -> Result<Self, Error> {
+        let opponent = game.opponent_move(opponent)?;
+        let me = game.move_for_outcome(opponent, game.response_index(response)?);
+        Ok(Self { opponent, me })
     }
 
-    /// Define the ordering: what does this position beat?
-    const fn beats(self) -> Self {
-        match self {
-            Rps::Rock => Rps::Scissors,
-            Rps::Paper => Rps::Rock,
-            Rps::Scissors => Rps::Paper,
-        }
+    fn score(self, game: &Game) -> u64 {
+        game.score(self.me, self.opponent)
     }
+}
 
-    // There doesn't seem to be an elegant way to derive this, unfortunately.
-    const fn loses_against(self) -> Self {
-        match self {
-            Rps::Rock => Rps::Paper,
-            Rps::Paper => Rps::Scissors,
-            Rps::Scissors => Rps::Rock,
-        }
-    }
+pub fn parse_str(input: &str) -> Result<Vec<Instruction>, Error> {
+    Game::default().parse_guide(input)
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Round {
-    opponent: Rps,
-    me: Rps,
+pub fn from_reader(mut reader: impl Read) -> Result<Vec<Instruction>, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_str(&input)
 }
 
-impl Round {
-    fn from_instruction_pt1(Instruction { opponent, other }: Instruction) -> Self {
-        let opponent = opponent.into();
-        let me = other.into();
-        Self { opponent, me }
+pub fn parse(input: &Path) -> Result<Vec<Instruction>, Error> {
+    parse_file(input, parse_str)
+}
+
+/// Total the score of every round, building each round from its instruction with `round`.
+fn total_score(
+    game: &Game,
+    instructions: &[Instruction],
+    round: fn(&Game, Instruction) -> Result<Round, Error>,
+) -> Result<Solution, Error> {
+    let total_score = instructions.iter().try_fold(0_u64, |total, &instruction| {
+        let score = round(game, instruction)?.score(game);
+        total.checked_add(score).ok_or(Error::ScoreOverflow)
+    })?;
+    Ok(Solution::new(total_score))
+}
+
+pub fn part1(instructions: &[Instruction]) -> Result<Solution, Error> {
+    part1_with(instructions, &Game::default())
+}
+
+pub fn part1_with(instructions: &[Instruction], game: &Game) -> Result<Solution, Error> {
+    total_score(game, instructions, Round::from_instruction_pt1)
+}
+
+pub fn part2(instructions: &[Instruction]) -> Result<Solution, Error> {
+    part2_with(instructions, &Game::default())
+}
+
+pub fn part2_with(instructions: &[Instruction], game: &Game) -> Result<Solution, Error> {
+    total_score(game, instructions, Round::from_instruction_pt2)
+}
+
+pub fn solve_part1(input: &Path) -> Result<Solution, Error> {
+    part1(&parse(input)?)
+}
+
+pub fn solve_part2(input: &Path) -> Result<Solution, Error> {
+    part2(&parse(input)?)
+}
+
+/// Parse a guide written for `game`.
+pub fn parse_with(input: &Path, game: &Game) -> Result<Vec<Instruction>, Error> {
+    parse_file(input, |input| game.parse_guide(input))
+}
+
+/// Solve part 1 of a guide written for `game`.
+pub fn solve_part1_with(input: &Path, game: &Game) -> Result<Solution, Error> {
+    part1_with(&parse_with(input, game)?, game)
+}
+
+/// Solve part 2 of a guide written for `game`.
+pub fn solve_part2_with(input: &Path, game: &Game) -> Result<Solution, Error> {
+    part2_with(&parse_with(input, game)?, game)
+}
+
+/// Day 2: Rock Paper Scissors
+pub struct Day02;
+
+impl Puzzle for Day02 {
+    const DAY: u8 = 2;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const LABELS: [&'static str; 2] = ["total score (pt. 1)", "total score (pt. 2)"];
+
+    type Input = Vec<Instruction>;
+    type Params = Params;
+    type Answer1 = Solution;
+    type Answer2 = Solution;
+    type Error = Error;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_str(input)
     }
 
-    fn from_instruction_pt2(Instruction { opponent, other }: Instruction) -> Self {
-        let opponent: Rps = opponent.into();
-        let me = match other {
-            OtherSigil::X => {
-                // we need to lose
-                opponent.beats()
-            }
-            OtherSigil::Y => {
-                // we need to draw
-                opponent
-            }
-            OtherSigil::Z => {
-                // we need to win
-                opponent.loses_against()
-            }
-        };
-        Self { opponent, me }
+    fn part1(input: &Self::Input, params: &Params) -> Result<Solution, Error> {
+        part1_with(input, &params.game())
     }
 
-    fn outcome_score(self) -> u32 {
-        if self.me.beats() == self.opponent {
-            6
-        } else if self.me.loses_against() == self.opponent {
-            0
-        } else {
-            debug_assert_eq!(self.me, self.opponent);
-            3
-        }
+    fn part2(input: &Self::Input, params: &Params) -> Result<Solution, Error> {
+        part2_with(input, &params.game())
     }
 
-    fn score(self) -> u32 {
-        self.outcome_score() + self.me.score()
+    /// Parses the guide with the sigils of the chosen game.
+    fn solve_file(path: &Path, part: u8, params: &Params) -> Result<Solution, Error> {
+        let game = params.game();
+        let input = parse_with(path, &game)?;
+        let _span = logging::part_span(Self::DAY, part).entered();
+        match part {
+            1 => part1_with(&input, &game),
+            2 => part2_with(&input, &game),
+            _ => Self::solve(&input, part, params),
+        }
     }
-}
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let total_score: u32 = parse(input)?
-        .map(|instruction| {
-            let round = Round::from_instruction_pt1(instruction);
-            round.score()
-        })
-        .sum();
-    println!("total score (pt. 1): {total_score}");
-    Ok(())
-}
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+        generate(rng, size)
+    }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let total_score: u32 = parse(input)?
-        .map(|instruction| {
-            let round = Round::from_instruction_pt2(instruction);
-            round.score()
-        })
-        .sum();
-    println!("total score (pt. 2): {total_score}");
-    Ok(())
+    fn render(input: &Self::Input) -> String {
+        join_lines(input)
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -156,4 +202,54 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("no solution found")]
     NoSolution,
+    #[error("parsing input")]
+    Parse(#[from] Diagnostic),
+    #[error("{sigil:?} is not a sigil of this game")]
+    UnknownSigil { sigil: char },
+    #[error("invalid game definition: {0}")]
+    Game(#[from] toml::de::Error),
+    #[error("total score overflows")]
+    ScoreOverflow,
+    #[error(
+        "a game of {moves} moves has too many interpretations to explore; the limit is {} moves",
+        explore::MAX_MOVES
+    )]
+    TooManyMoves { moves: usize },
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::Parse(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::Answer;
+
+    #[test]
+    fn example_part1() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part1(&input).unwrap().answer, Answer::from(15));
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part2(&input).unwrap().answer, Answer::from(12));
+    }
+
+    #[test]
+    fn cli_defaults_match_constants() {
+        assert_eq!(Params::from_iter(["day02"]), Params::default());
+    }
+
+    #[test]
+    fn render_round_trips() {
+        common::puzzle::assert_round_trips::<Day02>(0..8, 20);
+    }
 }
@@ -0,0 +1,38 @@
+use rand::{seq::SliceRandom, Rng};
+
+const ITEMS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Generate `size` elf groups: three rucksacks each.
+///
+/// Every rucksack has exactly one item type in both compartments, and every group has exactly
+/// one item type in all three rucksacks.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+    let mut input = String::new();
+    for _ in 0..size {
+        let badge = *ITEMS.choose(rng).unwrap();
+        // give each rucksack its own items, so that only the badge is common to all three
+        let mut others = ITEMS
+            .iter()
+            .copied()
+            .filter(|&item| item != badge)
+            .collect::<Vec<_>>();
+        others.shuffle(rng);
+
+        for pool in others.chunks(others.len() / 3) {
+            let (shared, pool) = pool.split_first().unwrap();
+            let (left_pool, right_pool) = pool.split_at(pool.len() / 2);
+            let half_len = rng.gen_range(2..=16);
+
+            let mut left = vec![*shared, badge];
+            let mut right = vec![*shared];
+            left.extend((left.len()..half_len).map(|_| *left_pool.choose(rng).unwrap()));
+            right.extend((right.len()..half_len).map(|_| *right_pool.choose(rng).unwrap()));
+            left.shuffle(rng);
+            right.shuffle(rng);
+
+            input.extend(left.into_iter().chain(right).map(char::from));
+            input.push('\n');
+        }
+    }
+    input
+}
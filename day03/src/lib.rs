@@ -1,11 +1,24 @@
-use aoclib::parse;
+use common::{
+    diagnostic::{parse_file, parse_lines, Diagnosed},
+    input::join_lines,
+    puzzle::NoParams,
+    Diagnostic, Puzzle, Solution,
+};
 use itertools::Itertools;
-use std::{path::Path, str::FromStr};
+use rand::Rng;
+use std::{io::Read, path::Path, str::FromStr};
+
+mod generate;
+
+pub use generate::generate;
+
+/// The worked example from the puzzle description.
+pub const EXAMPLE: &str = include_str!("../example.txt");
 
 fn priority_of(value: char) -> Result<u8, Error> {
-    if ('a'..='z').contains(&value) {
+    if value.is_ascii_lowercase() {
         Ok(value as u8 - b'a' + 1)
-    } else if ('A'..='Z').contains(&value) {
+    } else if value.is_ascii_uppercase() {
         Ok(value as u8 - b'A' + 1 + 26)
     } else {
         Err(Error::MalformedPriority(value))
@@ -93,10 +106,24 @@ fn find_badge(group: &[Priorities]) -> Result<u8, Error> {
     unreachable!("we can exhaustively search a u64");
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn parse_str(input: &str) -> Result<Vec<String>, Error> {
+    Ok(parse_lines(input)?)
+}
+
+pub fn from_reader(mut reader: impl Read) -> Result<Vec<String>, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_str(&input)
+}
+
+pub fn parse(input: &Path) -> Result<Vec<String>, Error> {
+    parse_file(input, parse_str)
+}
+
+pub fn part1(rucksacks: &[String]) -> Result<Solution, Error> {
     let mut mutual_priority_sum = 0;
-    for rucksack_contents in parse::<String>(input)? {
-        let (left, right) = halve_string(rucksack_contents)?;
+    for rucksack_contents in rucksacks {
+        let (left, right) = halve_string(rucksack_contents.clone())?;
         let left: Priorities = left.parse()?;
         let right: Priorities = right.parse()?;
         let intersection = left.as_flags() & right.as_flags();
@@ -106,20 +133,61 @@ pub fn part1(input: &Path) -> Result<(), Error> {
             }
         }
     }
-    println!("mutual priority sum: {mutual_priority_sum}");
-    Ok(())
+    Ok(Solution::new(mutual_priority_sum))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(rucksacks: &[String]) -> Result<Solution, Error> {
     let mut badge_sum = 0;
-    for chunk in parse::<String>(input)?.chunks(3).into_iter() {
+    for chunk in rucksacks.iter().chunks(3).into_iter() {
         let (left, mid, right) = chunk.collect_tuple().ok_or(Error::IncompleteGroup)?;
         let priorities = [left.parse::<Priorities>()?, mid.parse()?, right.parse()?];
         let badge = find_badge(&priorities)?;
         badge_sum += badge as u32;
     }
-    println!("sum of priorities of group badges: {badge_sum}");
-    Ok(())
+    Ok(Solution::new(badge_sum))
+}
+
+pub fn solve_part1(input: &Path) -> Result<Solution, Error> {
+    part1(&parse(input)?)
+}
+
+pub fn solve_part2(input: &Path) -> Result<Solution, Error> {
+    part2(&parse(input)?)
+}
+
+/// Day 3: Rucksack Reorganization
+pub struct Day03;
+
+impl Puzzle for Day03 {
+    const DAY: u8 = 3;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const LABELS: [&'static str; 2] = ["mutual priority sum", "sum of priorities of group badges"];
+
+    type Input = Vec<String>;
+    type Params = NoParams;
+    type Answer1 = Solution;
+    type Answer2 = Solution;
+    type Error = Error;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_str(input)
+    }
+
+    fn part1(input: &Self::Input, _params: &NoParams) -> Result<Solution, Error> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input, _params: &NoParams) -> Result<Solution, Error> {
+        part2(input)
+    }
+
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn render(input: &Self::Input) -> String {
+        join_lines(input)
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -136,4 +204,38 @@ pub enum Error {
     IncompleteGroup,
     #[error("expected 1 common item; got {0}")]
     WrongCountCommonItems(u32),
+    #[error("parsing input")]
+    Parse(#[from] Diagnostic),
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::Parse(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::Answer;
+
+    #[test]
+    fn example_part1() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part1(&input).unwrap().answer, Answer::from(157));
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part2(&input).unwrap().answer, Answer::from(70));
+    }
+
+    #[test]
+    fn render_round_trips() {
+        common::puzzle::assert_round_trips::<Day03>(0..8, 20);
+    }
 }
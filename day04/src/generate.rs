@@ -0,0 +1,17 @@
+use rand::Rng;
+use std::fmt::Write;
+
+/// Generate `size` pairs of section assignments.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+    let mut range = || {
+        let low = rng.gen_range(1..=99);
+        (low, rng.gen_range(low..=99))
+    };
+    let mut input = String::new();
+    for _ in 0..size {
+        let (a, b) = range();
+        let (c, d) = range();
+        writeln!(input, "{a}-{b},{c}-{d}").unwrap();
+    }
+    input
+}
@@ -1,8 +1,21 @@
-use aoclib::parse;
+use common::{
+    diagnostic::{parse_file, parse_lines, Diagnosed},
+    input::join_lines,
+    puzzle::NoParams,
+    Diagnostic, Puzzle, Solution,
+};
 use parse_display::{Display, FromStr};
-use std::path::Path;
+use rand::Rng;
+use std::{io::Read, path::Path};
 
-#[derive(Debug, Clone, Copy, FromStr, Display)]
+mod generate;
+
+pub use generate::generate;
+
+/// The worked example from the puzzle description.
+pub const EXAMPLE: &str = include_str!("../example.txt");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromStr, Display)]
 #[display("{low}-{high}")]
 struct Assignment {
     low: u32,
@@ -19,29 +32,86 @@ impl Assignment {
     }
 }
 
-#[derive(Debug, Clone, Copy, FromStr, Display)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromStr, Display)]
 #[display("{left},{right}")]
-struct Pair {
+pub struct Pair {
     left: Assignment,
     right: Assignment,
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let fully_contained = parse::<Pair>(input)?
+pub fn parse_str(input: &str) -> Result<Vec<Pair>, Error> {
+    Ok(parse_lines(input)?)
+}
+
+pub fn from_reader(mut reader: impl Read) -> Result<Vec<Pair>, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_str(&input)
+}
+
+pub fn parse(input: &Path) -> Result<Vec<Pair>, Error> {
+    parse_file(input, parse_str)
+}
+
+pub fn part1(pairs: &[Pair]) -> Result<Solution, Error> {
+    let fully_contained = pairs
+        .iter()
         .filter(|pair| {
             pair.left.fully_contains(&pair.right) || pair.right.fully_contains(&pair.left)
         })
         .count();
-    println!("fully contained: {fully_contained}");
-    Ok(())
+    Ok(Solution::new(fully_contained))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let overlaps = parse::<Pair>(input)?
+pub fn part2(pairs: &[Pair]) -> Result<Solution, Error> {
+    let overlaps = pairs
+        .iter()
         .filter(|pair| pair.left.overlaps_low(&pair.right) || pair.right.overlaps_low(&pair.left))
         .count();
-    println!("overlaps: {overlaps}");
-    Ok(())
+    Ok(Solution::new(overlaps))
+}
+
+pub fn solve_part1(input: &Path) -> Result<Solution, Error> {
+    part1(&parse(input)?)
+}
+
+pub fn solve_part2(input: &Path) -> Result<Solution, Error> {
+    part2(&parse(input)?)
+}
+
+/// Day 4: Camp Cleanup
+pub struct Day04;
+
+impl Puzzle for Day04 {
+    const DAY: u8 = 4;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const LABELS: [&'static str; 2] = ["fully contained", "overlaps"];
+
+    type Input = Vec<Pair>;
+    type Params = NoParams;
+    type Answer1 = Solution;
+    type Answer2 = Solution;
+    type Error = Error;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_str(input)
+    }
+
+    fn part1(input: &Self::Input, _params: &NoParams) -> Result<Solution, Error> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input, _params: &NoParams) -> Result<Solution, Error> {
+        part2(input)
+    }
+
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn render(input: &Self::Input) -> String {
+        join_lines(input)
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -50,4 +120,38 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("no solution found")]
     NoSolution,
+    #[error("parsing input")]
+    Parse(#[from] Diagnostic),
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::Parse(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::Answer;
+
+    #[test]
+    fn example_part1() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part1(&input).unwrap().answer, Answer::from(2));
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part2(&input).unwrap().answer, Answer::from(4));
+    }
+
+    #[test]
+    fn render_round_trips() {
+        common::puzzle::assert_round_trips::<Day04>(0..8, 20);
+    }
 }
@@ -0,0 +1,39 @@
+use crate::{Movement, Procedure, Stacks};
+use rand::{seq::SliceRandom, Rng};
+
+/// Generate starting stacks and a procedure of `size` movements, each of which is possible.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+    let n_stacks = rng.gen_range(3..=9);
+    let stacks = (0..n_stacks)
+        .map(|_| {
+            let height = rng.gen_range(1..=8);
+            (0..height).map(|_| rng.gen_range(b'A'..=b'Z')).collect()
+        })
+        .collect::<Vec<Vec<u8>>>();
+
+    let mut heights = stacks.iter().map(Vec::len).collect::<Vec<_>>();
+    let movements = (0..size)
+        .map(|_| {
+            let occupied = (0..n_stacks)
+                .filter(|&idx| heights[idx] > 0)
+                .collect::<Vec<_>>();
+            let origin = *occupied.choose(rng).unwrap();
+            let destination = (origin + rng.gen_range(1..n_stacks)) % n_stacks;
+            let qty = rng.gen_range(1..=heights[origin]);
+            heights[origin] -= qty;
+            heights[destination] += qty;
+            Movement {
+                qty,
+                // movements are 1-indexed
+                origin: origin + 1,
+                destination: destination + 1,
+            }
+        })
+        .collect();
+
+    Procedure {
+        stacks: Stacks(stacks),
+        movements,
+    }
+    .to_string()
+}
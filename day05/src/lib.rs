@@ -1,53 +1,72 @@
+use common::{
+    diagnostic::{lines, parse_file, Diagnosed},
+    puzzle::NoParams,
+    repl::{Interactive, Simulation},
+    Diagnostic, Puzzle, Solution,
+};
 use parse_display::Display;
-use std::{path::Path, str::FromStr};
+use rand::Rng;
+use std::{fmt, io::Read, path::Path, str::FromStr};
 
-#[derive(Debug, Clone, Copy, parse_display::FromStr, Display)]
+mod generate;
+
+pub use generate::generate;
+
+/// The worked example from the puzzle description.
+pub const EXAMPLE: &str = include_str!("../example.txt");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, parse_display::FromStr, Display)]
 #[display("move {qty} from {origin} to {destination}")]
-struct Movement {
+pub struct Movement {
     qty: usize,
     origin: usize,
     destination: usize,
 }
 
-#[derive(Debug, Clone)]
-struct Stacks(Vec<Vec<u8>>);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stacks(Vec<Vec<u8>>);
 
 impl FromStr for Stacks {
-    type Err = Error;
+    type Err = Diagnostic;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let malformed = |span: &str, reason: &str| {
+            Diagnostic::new(s, span, format!("malformed stacks: {reason}"))
+        };
+
         let mut lines = s.lines().rev().filter(|line| !line.trim().is_empty());
         let indices = lines
             .next()
-            .ok_or(Error::MalformedStacks("no index line found"))?;
-        if indices
+            .ok_or_else(|| malformed(s, "no index line found"))?;
+        if let Some(idx) = indices
             .split_whitespace()
-            .any(|idx| idx.parse::<usize>().is_err())
+            .find(|idx| idx.parse::<usize>().is_err())
         {
-            return Err(Error::MalformedStacks("parsing indices as numbers"));
+            return Err(malformed(idx, "parsing indices as numbers"));
         }
         let n_stacks: usize = indices
             .split_whitespace()
             .last()
-            .ok_or(Error::MalformedStacks("no index numbers found"))?
+            .ok_or_else(|| malformed(indices, "no index numbers found"))?
             .parse()
-            .map_err(|_| Error::MalformedStacks("parsing final index as usize"))?;
+            .map_err(|_| malformed(indices, "parsing final index as usize"))?;
 
         let mut stacks = vec![Vec::new(); n_stacks];
 
         for line in lines {
-            let line = line.as_bytes();
+            let bytes = line.as_bytes();
             for (idx, stack) in stacks.iter_mut().enumerate() {
                 let start = idx * 4;
-                if line.len() < start + 3 {
+                if bytes.len() < start + 3 {
                     break;
                 }
-                if line[start] == b' ' && line[start + 2] == b' ' {
+                if bytes[start] == b' ' && bytes[start + 2] == b' ' {
                     continue;
-                } else if line[start] != b'[' || line[start + 2] != b']' {
-                    return Err(Error::MalformedStacks("did not find crate edges"));
+                } else if bytes[start] != b'[' || bytes[start + 2] != b']' {
+                    let found = line.get(start..start + 3).unwrap_or(line);
+                    return Err(malformed(found, "did not find crate edges"));
                 }
-                stack.push(line[start + 1]);
+                stack.push(bytes[start + 1]);
             }
         }
 
@@ -55,6 +74,29 @@ impl FromStr for Stacks {
     }
 }
 
+impl fmt::Display for Stacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let height = self.0.iter().map(Vec::len).max().unwrap_or_default();
+        for row in (0..height).rev() {
+            let line = self
+                .0
+                .iter()
+                .map(|stack| match stack.get(row) {
+                    Some(&item) => format!("[{}]", item as char),
+                    None => "   ".to_owned(),
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(f, "{line}")?;
+        }
+        let indices = (1..=self.0.len())
+            .map(|idx| format!(" {idx} "))
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(f, "{indices}")
+    }
+}
+
 impl Stacks {
     fn tops(&self) -> String {
         self.0
@@ -64,72 +106,238 @@ impl Stacks {
     }
 }
 
-fn parse(input: &Path) -> Result<(Stacks, Vec<Movement>), Error> {
-    use aoclib::input::{parse_str, parse_two_phase};
+/// The starting stacks, and the rearrangement procedure to apply to them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Procedure {
+    pub stacks: Stacks,
+    pub movements: Vec<Movement>,
+}
 
-    struct MovementParser(Vec<Movement>);
+impl FromStr for Procedure {
+    type Err = Error;
 
-    impl FromStr for MovementParser {
-        type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (stacks, movements) = s.split_once("\n\n").ok_or_else(|| {
+            Diagnostic::new(
+                s,
+                &s[s.len()..],
+                "no blank line separating the stacks from the movements",
+            )
+        })?;
+        let stacks: Stacks = stacks.parse()?;
+        let n_stacks = stacks.0.len();
+        let movements = lines(movements)
+            .map(|line| {
+                let movement: Movement = line.parse().map_err(|err| {
+                    Diagnostic::new(s, line, format!("could not parse movement: {err}"))
+                })?;
+                // the format is `move {qty} from {origin} to {destination}`
+                let words = line.split_whitespace().collect::<Vec<_>>();
+                for (stack, word) in [
+                    (movement.origin, words[3]),
+                    (movement.destination, words[5]),
+                ] {
+                    if !(1..=n_stacks).contains(&stack) {
+                        return Err(Diagnostic::new(
+                            s,
+                            word,
+                            format!("no stack {stack}; stacks are numbered 1 to {n_stacks}"),
+                        ));
+                    }
+                }
+                Ok(movement)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Procedure { stacks, movements })
+    }
+}
 
-        fn from_str(s: &str) -> Result<Self, Self::Err> {
-            Ok(MovementParser(parse_str(s)?.collect()))
+impl fmt::Display for Procedure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.stacks)?;
+        for movement in &self.movements {
+            writeln!(f, "{movement}")?;
         }
+        Ok(())
     }
-
-    parse_two_phase::<'_, Stacks, MovementParser>(input)
-        .map(|(stacks, movement_sets)| {
-            (
-                stacks,
-                movement_sets.flat_map(|mp| mp.0.into_iter()).collect(),
-            )
-        })
-        .map_err(Into::into)
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let (mut stacks, movements) = parse(input)?;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Model {
+    /// Moves crates one at a time.
+    CrateMover9000,
+    /// Moves several crates at once, keeping their order.
+    CrateMover9001,
+}
 
-    for (movement_idx, movement) in movements.iter().enumerate() {
-        let origin = movement.origin - 1; // movements are 1-indexed
-        let destination = movement.destination - 1; // movements are 1-indexed
+/// A crane partway through a rearrangement procedure.
+#[derive(Debug, Clone)]
+pub struct Crane {
+    stacks: Stacks,
+    movements: Vec<Movement>,
+    /// The index of the next movement to make.
+    next: usize,
+    model: Model,
+    scratch_space: Vec<u8>,
+}
 
-        for _ in 0..movement.qty {
-            let top = stacks.0[origin]
-                .pop()
-                .ok_or(Error::StackUnderflow(movement_idx, movement.origin))?;
-            stacks.0[destination].push(top);
+impl Crane {
+    fn new(procedure: &Procedure, model: Model) -> Self {
+        let Procedure { stacks, movements } = procedure.clone();
+        Self {
+            stacks,
+            movements,
+            next: 0,
+            model,
+            scratch_space: Vec::new(),
         }
     }
-
-    println!("stack tops (pt. 1): {}", stacks.tops());
-    Ok(())
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let (mut stacks, movements) = parse(input)?;
-    let mut scratch_space = Vec::<u8>::new();
+impl Simulation for Crane {
+    type Error = Error;
 
-    for (movement_idx, movement) in movements.iter().enumerate() {
+    /// Make the next movement.
+    fn step(&mut self) -> Result<bool, Error> {
+        let Some(&movement) = self.movements.get(self.next) else {
+            return Ok(false);
+        };
+        let movement_idx = self.next;
+        let stacks = &mut self.stacks.0;
         let origin = movement.origin - 1; // movements are 1-indexed
         let destination = movement.destination - 1; // movements are 1-indexed
 
-        if stacks.0[origin].len() < movement.qty {
+        if stacks[origin].len() < movement.qty {
             return Err(Error::StackUnderflow(movement_idx, movement.origin));
         }
 
-        let from_idx = stacks.0[origin].len() - movement.qty;
-        // We need to use the scratch space as a temporary here so that we don't
-        // borrow `stacks` both mutably and immutably. Such a borrow would be
-        // legitimate: we'd be deconflicting based on the distinct `origin` and
-        // `destination` indices, but we can't easily prove that to the compiler
-        // right now. An extra copy shouldn't be too expensive, hopefully.
-        scratch_space.extend(stacks.0[origin].drain(from_idx..));
-        stacks.0[destination].append(&mut scratch_space);
+        match self.model {
+            Model::CrateMover9000 => {
+                for _ in 0..movement.qty {
+                    let top = stacks[origin]
+                        .pop()
+                        .expect("stack was checked for underflow");
+                    stacks[destination].push(top);
+                }
+            }
+            Model::CrateMover9001 => {
+                let from_idx = stacks[origin].len() - movement.qty;
+                // We need to use the scratch space as a temporary here so that we don't
+                // borrow `stacks` both mutably and immutably. Such a borrow would be
+                // legitimate: we'd be deconflicting based on the distinct `origin` and
+                // `destination` indices, but we can't easily prove that to the compiler
+                // right now. An extra copy shouldn't be too expensive, hopefully.
+                self.scratch_space.extend(stacks[origin].drain(from_idx..));
+                stacks[destination].append(&mut self.scratch_space);
+            }
+        }
+
+        self.next += 1;
+        Ok(true)
     }
 
-    println!("stack tops (pt. 2): {}", stacks.tops());
-    Ok(())
+    fn probes(&self) -> Vec<(String, i64)> {
+        let mut probes = vec![("movement".to_owned(), self.next as i64)];
+        probes.extend(
+            self.stacks
+                .0
+                .iter()
+                .enumerate()
+                .map(|(idx, stack)| (format!("stack{}", idx + 1), stack.len() as i64)),
+        );
+        probes
+    }
+}
+
+impl fmt::Display for Crane {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.stacks)?;
+        match self.movements.get(self.next) {
+            Some(movement) => write!(f, "next: {movement}"),
+            None => write!(f, "tops: {}", self.stacks.tops()),
+        }
+    }
+}
+
+pub fn parse_str(input: &str) -> Result<Procedure, Error> {
+    input.parse()
+}
+
+pub fn from_reader(mut reader: impl Read) -> Result<Procedure, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_str(&input)
+}
+
+pub fn parse(input: &Path) -> Result<Procedure, Error> {
+    parse_file(input, parse_str)
+}
+
+pub fn part1(procedure: &Procedure) -> Result<Solution, Error> {
+    let mut crane = Crane::new(procedure, Model::CrateMover9000);
+    while crane.step()? {}
+    Ok(Solution::new(crane.stacks.tops()))
+}
+
+pub fn part2(procedure: &Procedure) -> Result<Solution, Error> {
+    let mut crane = Crane::new(procedure, Model::CrateMover9001);
+    while crane.step()? {}
+    Ok(Solution::new(crane.stacks.tops()))
+}
+
+pub fn solve_part1(input: &Path) -> Result<Solution, Error> {
+    part1(&parse(input)?)
+}
+
+pub fn solve_part2(input: &Path) -> Result<Solution, Error> {
+    part2(&parse(input)?)
+}
+
+/// Day 5: Supply Stacks
+pub struct Day05;
+
+impl Puzzle for Day05 {
+    const DAY: u8 = 5;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const LABELS: [&'static str; 2] = ["stack tops (pt. 1)", "stack tops (pt. 2)"];
+
+    type Input = Procedure;
+    type Params = NoParams;
+    type Answer1 = Solution;
+    type Answer2 = Solution;
+    type Error = Error;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_str(input)
+    }
+
+    fn part1(input: &Self::Input, _params: &NoParams) -> Result<Solution, Error> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input, _params: &NoParams) -> Result<Solution, Error> {
+        part2(input)
+    }
+
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn render(input: &Self::Input) -> String {
+        input.to_string()
+    }
+}
+
+impl Interactive for Day05 {
+    type Simulation = Crane;
+
+    fn simulate(input: &Procedure, part: u8) -> Crane {
+        let model = match part {
+            1 => Model::CrateMover9000,
+            _ => Model::CrateMover9001,
+        };
+        Crane::new(input, model)
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -138,10 +346,53 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("no solution found")]
     NoSolution,
-    #[error("malformed stacks: {0}")]
-    MalformedStacks(&'static str),
-    #[error(transparent)]
-    TwoPhase(#[from] aoclib::input::TwoPhaseError),
     #[error("stack underflow in movement {0} stack {1}")]
     StackUnderflow(usize, usize),
+    #[error("parsing input")]
+    Parse(#[from] Diagnostic),
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::Parse(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::Answer;
+
+    #[test]
+    fn example_part1() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part1(&input).unwrap().answer, Answer::from("CMZ"));
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part2(&input).unwrap().answer, Answer::from("MCD"));
+    }
+
+    #[test]
+    fn stack_numbers_are_checked() {
+        let (stacks, _) = EXAMPLE.split_once("\n\n").unwrap();
+        for (movement, column) in [("move 1 from 0 to 1", 13), ("move 1 from 2 to 4", 18)] {
+            let err = parse_str(&format!("{stacks}\n\n{movement}\n")).unwrap_err();
+            let Error::Parse(diagnostic) = err else {
+                panic!("{err:?}");
+            };
+            assert_eq!(diagnostic.column, column, "{movement}");
+            assert_eq!(diagnostic.snippet, movement);
+        }
+    }
+
+    #[test]
+    fn render_round_trips() {
+        common::puzzle::assert_round_trips::<Day05>(0..8, 20);
+    }
 }
@@ -0,0 +1,19 @@
+use rand::{seq::SliceRandom, Rng};
+
+/// Generate a datastream `size` chars long, or at least 14.
+///
+/// Everything before the final 14 chars is drawn from too few letters to contain a
+/// start-of-message marker, so the search for one has to reach the end.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+    let mut letters = (b'a'..=b'z').collect::<Vec<_>>();
+    letters.shuffle(rng);
+    let (marker, _) = letters.split_at(14);
+    let (_, filler) = letters.split_at(13);
+
+    let mut input = (0..size.max(14) - 14)
+        .map(|_| char::from(*filler.choose(rng).unwrap()))
+        .collect::<String>();
+    input.extend(marker.iter().copied().map(char::from));
+    input.push('\n');
+    input
+}
@@ -1,5 +1,18 @@
-use aoclib::parse;
-use std::path::Path;
+use common::{
+    diagnostic::{parse_file, parse_lines, Diagnosed},
+    input::join_lines,
+    puzzle::NoParams,
+    Diagnostic, Puzzle, Solution,
+};
+use rand::Rng;
+use std::{io::Read, path::Path};
+
+mod generate;
+
+pub use generate::generate;
+
+/// The worked example from the puzzle description.
+pub const EXAMPLE: &str = include_str!("../example.txt");
 
 const PACKET_SIZE: usize = 4;
 const MESSAGE_SIZE: usize = 14;
@@ -20,20 +33,89 @@ fn find_start(data: &[u8], size: usize) -> Result<usize, Error> {
     Ok(window_idx + size)
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    for (idx, input) in parse::<String>(input)?.enumerate() {
-        let packet_start = find_start(input.as_bytes(), PACKET_SIZE)?;
-        println!("pt. 1 idx {idx} packet start: {packet_start}");
+/// Find the start marker for each line of the input.
+///
+/// The answer is the marker position in the first line; if the input contains several
+/// datastreams, the position for each is reported as an extra line.
+fn solve(datastreams: &[String], size: usize, label: &str) -> Result<Solution, Error> {
+    let starts = datastreams
+        .iter()
+        .map(|line| find_start(line.as_bytes(), size))
+        .collect::<Result<Vec<_>, _>>()?;
+    let first = *starts.first().ok_or(Error::NoSolution)?;
+
+    let mut solution = Solution::new(first);
+    if starts.len() > 1 {
+        for (idx, start) in starts.into_iter().enumerate() {
+            solution = solution.with_extra(format!("idx {idx} {label} start: {start}"));
+        }
     }
-    Ok(())
+    Ok(solution)
+}
+
+pub fn parse_str(input: &str) -> Result<Vec<String>, Error> {
+    Ok(parse_lines(input)?)
+}
+
+pub fn from_reader(mut reader: impl Read) -> Result<Vec<String>, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_str(&input)
+}
+
+pub fn parse(input: &Path) -> Result<Vec<String>, Error> {
+    parse_file(input, parse_str)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    for (idx, input) in parse::<String>(input)?.enumerate() {
-        let message_start = find_start(input.as_bytes(), MESSAGE_SIZE)?;
-        println!("pt. 1 idx {idx} packet start: {message_start}");
+pub fn part1(datastreams: &[String]) -> Result<Solution, Error> {
+    solve(datastreams, PACKET_SIZE, "packet")
+}
+
+pub fn part2(datastreams: &[String]) -> Result<Solution, Error> {
+    solve(datastreams, MESSAGE_SIZE, "message")
+}
+
+pub fn solve_part1(input: &Path) -> Result<Solution, Error> {
+    part1(&parse(input)?)
+}
+
+pub fn solve_part2(input: &Path) -> Result<Solution, Error> {
+    part2(&parse(input)?)
+}
+
+/// Day 6: Tuning Trouble
+pub struct Day06;
+
+impl Puzzle for Day06 {
+    const DAY: u8 = 6;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const LABELS: [&'static str; 2] = ["pt. 1 packet start", "pt. 2 message start"];
+
+    type Input = Vec<String>;
+    type Params = NoParams;
+    type Answer1 = Solution;
+    type Answer2 = Solution;
+    type Error = Error;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_str(input)
+    }
+
+    fn part1(input: &Self::Input, _params: &NoParams) -> Result<Solution, Error> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input, _params: &NoParams) -> Result<Solution, Error> {
+        part2(input)
+    }
+
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn render(input: &Self::Input) -> String {
+        join_lines(input)
     }
-    Ok(())
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -42,13 +124,37 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("no solution found")]
     NoSolution,
+    #[error("parsing input")]
+    Parse(#[from] Diagnostic),
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::Parse(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::Answer;
     use rstest::rstest;
 
+    #[test]
+    fn example_part1() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part1(&input).unwrap().answer, Answer::from(7));
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part2(&input).unwrap().answer, Answer::from(19));
+    }
+
     #[rstest]
     #[case("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7)]
     #[case("bvwbjplbgvbhsrlpgdmjqwftvncz", 5)]
@@ -70,4 +176,9 @@ mod tests {
         let have = find_start(input.as_bytes(), MESSAGE_SIZE).expect("found a message");
         assert_eq!(have, expect);
     }
+
+    #[test]
+    fn render_round_trips() {
+        common::puzzle::assert_round_trips::<Day06>(0..8, 20);
+    }
 }
@@ -0,0 +1,88 @@
+use rand::{seq::SliceRandom, Rng};
+use std::{collections::HashSet, fmt::Write};
+
+#[derive(Default)]
+struct Dir {
+    name: String,
+    children: Vec<usize>,
+    files: Vec<(u64, String)>,
+    /// Names of children and files, which must not collide.
+    names: HashSet<String>,
+}
+
+impl Dir {
+    fn unique_name<R: Rng + ?Sized>(&mut self, rng: &mut R, extension: bool) -> String {
+        loop {
+            let mut name = (0..rng.gen_range(1..=8))
+                .map(|_| char::from(rng.gen_range(b'a'..=b'z')))
+                .collect::<String>();
+            if extension && rng.gen_bool(0.5) {
+                name.push_str(["txt", "dat", "log", "lst", "ext"].choose(rng).unwrap());
+                name.insert(name.len() - 3, '.');
+            }
+            if self.names.insert(name.clone()) {
+                return name;
+            }
+        }
+    }
+}
+
+fn transcript(dirs: &[Dir], idx: usize, input: &mut String) {
+    let dir = &dirs[idx];
+    writeln!(input, "$ ls").unwrap();
+    for &child in &dir.children {
+        writeln!(input, "dir {}", dirs[child].name).unwrap();
+    }
+    for (size, name) in &dir.files {
+        writeln!(input, "{size} {name}").unwrap();
+    }
+    for &child in &dir.children {
+        writeln!(input, "$ cd {}", dirs[child].name).unwrap();
+        transcript(dirs, child, input);
+        writeln!(input, "$ cd ..").unwrap();
+    }
+}
+
+/// Generate a terminal transcript exploring a filesystem of `size` files, plus one more.
+///
+/// The extra file in the root brings the disk usage to between 41M and 69M, so that part 2 always
+/// has to delete something, and always can.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+    let mut dirs = vec![Dir::default()];
+    let max_file_size = (40_000_000 / size.max(1) as u64).clamp(1, 400_000);
+    let mut used = 0;
+
+    for _ in 0..size {
+        let mut parent = rng.gen_range(0..dirs.len());
+        if rng.gen_bool(0.3) {
+            let name = dirs[parent].unique_name(rng, false);
+            let child = dirs.len();
+            dirs[parent].children.push(child);
+            parent = child;
+            dirs.push(Dir {
+                name,
+                ..Dir::default()
+            });
+        }
+        let file_size = rng.gen_range(1..=max_file_size);
+        used += file_size;
+        let name = dirs[parent].unique_name(rng, true);
+        dirs[parent].files.push((file_size, name));
+    }
+
+    let filler = rng.gen_range(41_000_000..=69_000_000) - used;
+    let name = dirs[0].unique_name(rng, true);
+    dirs[0].files.push((filler, name));
+    for dir in &mut dirs {
+        dir.files.shuffle(rng);
+    }
+
+    let mut input = "$ cd /\n".to_owned();
+    transcript(&dirs, 0, &mut input);
+    // the session ends once the last directory has been listed, without climbing back out
+    while let Some(rest) = input.strip_suffix("$ cd ..\n") {
+        let len = rest.len();
+        input.truncate(len);
+    }
+    input
+}
@@ -1,11 +1,26 @@
-use aoclib::parse;
+use common::{
+    diagnostic::{parse_file, parse_lines, Diagnosed},
+    puzzle::NoParams,
+    Diagnostic, Puzzle, Solution,
+};
 use parse_display::{Display, FromStr};
+use rand::Rng;
 use std::{
     cell::Cell,
+    collections::BTreeMap,
+    fmt,
+    io::Read,
     ops::{Index, IndexMut},
     path::Path,
 };
 
+mod generate;
+
+pub use generate::generate;
+
+/// The worked example from the puzzle description.
+pub const EXAMPLE: &str = include_str!("../example.txt");
+
 #[derive(Default, Debug, Clone, Copy, derive_more::From)]
 enum Inode {
     #[default]
@@ -122,7 +137,7 @@ impl Node {
     }
 }
 
-struct Filesystem {
+pub struct Filesystem {
     arena: Vec<Node>,
     root: Node,
 }
@@ -138,6 +153,64 @@ impl Filesystem {
     fn iter(&self) -> impl '_ + Iterator<Item = &Node> {
         std::iter::once(&self.root).chain(self.arena.iter())
     }
+
+    /// The path of every node, with the size of each file; directories have none of their own.
+    fn entries(&self) -> BTreeMap<String, Option<u64>> {
+        self.iter()
+            .map(|node| {
+                let size = match node {
+                    Node::Dir(_) => None,
+                    Node::File(file) => Some(file.size),
+                };
+                (node.metadata().path(self), size)
+            })
+            .collect()
+    }
+
+    /// The terminal lines which list `dir` and then explore each of its subdirectories.
+    fn transcript(&self, dir: &Dir, lines: &mut Vec<String>) {
+        lines.push(Line::Ls.to_string());
+        for &child in &dir.children {
+            lines.push(match &self[child] {
+                Node::Dir(dir) => dir.to_string(),
+                Node::File(file) => file.to_string(),
+            });
+        }
+        for child in dir
+            .children
+            .iter()
+            .filter_map(|&child| self[child].as_dir())
+        {
+            lines.push(Line::Cd(child.metadata.name.clone()).to_string());
+            self.transcript(child, lines);
+            lines.push(Line::Cd("..".into()).to_string());
+        }
+    }
+}
+
+/// Filesystems are equal when they hold the same files and directories at the same paths, however
+/// their transcripts explored them.
+impl PartialEq for Filesystem {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries() == other.entries()
+    }
+}
+
+impl Eq for Filesystem {}
+
+impl fmt::Display for Filesystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = vec![Line::Cd("/".into()).to_string()];
+        self.transcript(self.root.as_dir().expect("root is a dir"), &mut lines);
+        // the session ends once the last directory has been listed, without climbing back out
+        while lines.last().is_some_and(|line| line == "$ cd ..") {
+            lines.pop();
+        }
+        for line in lines {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
 }
 
 impl FromIterator<Line> for Filesystem {
@@ -194,8 +267,9 @@ impl FromIterator<Line> for Filesystem {
                                 dir.metadata.name == child_name
                             })
                             .unwrap_or_else(|| {
-                                eprintln!(
-                                    "attempting to cd to unknown child directory: {child_name}"
+                                tracing::warn!(
+                                    child_name,
+                                    "attempting to cd to unknown child directory"
                                 );
                                 Inode::default()
                             }),
@@ -233,47 +307,99 @@ impl IndexMut<Inode> for Filesystem {
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let fs: Filesystem = parse(input)?.collect();
+pub fn parse_str(input: &str) -> Result<Filesystem, Error> {
+    Ok(parse_lines(input)?)
+}
+
+pub fn from_reader(mut reader: impl Read) -> Result<Filesystem, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_str(&input)
+}
+
+pub fn parse(input: &Path) -> Result<Filesystem, Error> {
+    parse_file(input, parse_str)
+}
+
+pub fn part1(fs: &Filesystem) -> Result<Solution, Error> {
     let size_sum: u64 = fs
         .iter()
         .filter_map(|node| {
             node.as_dir().and_then(|dir| {
-                let size = dir.size(&fs);
+                let size = dir.size(fs);
                 (size < 100_000).then_some(size)
             })
         })
         .sum();
-    println!("size sum: {size_sum}");
-    Ok(())
+    Ok(Solution::new(size_sum))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let fs: Filesystem = parse(input)?.collect();
-
+pub fn part2(fs: &Filesystem) -> Result<Solution, Error> {
     let total_disk_space: u64 = 70_000_000;
     let need_unused_space: u64 = 30_000_000;
-    let used_space = fs.root.size(&fs);
-    let Some(unused_space) = total_disk_space.checked_sub(used_space) else {
-        println!("pt. 2: used space is greater than total disk space!?");
-        return Ok(());
-    };
+    let used_space = fs.root.size(fs);
+    let unused_space = total_disk_space
+        .checked_sub(used_space)
+        .ok_or(Error::DiskOverfull(used_space))?;
     let Some(need_to_clear) = need_unused_space.checked_sub(unused_space) else {
-        println!("pt. 2: don't need to clear any directories");
-        return Ok(());
+        // a text answer, so that it can't be mistaken for a directory size
+        return Ok(Solution::new("nothing").with_extra(format!(
+            "{unused_space} bytes are already unused; no directory needs deleting"
+        )));
     };
 
     let smallest_deleteable_directory = fs
         .iter()
         .filter_map(|node| node.as_dir())
-        .filter(|dir| dir.size(&fs) >= need_to_clear)
-        .min_by_key(|dir| dir.size(&fs))
+        .filter(|dir| dir.size(fs) >= need_to_clear)
+        .min_by_key(|dir| dir.size(fs))
         .expect("at least one directory is big enough that deleting it clears enough space");
-    let path = smallest_deleteable_directory.metadata.path(&fs);
-    let size = smallest_deleteable_directory.size(&fs);
-    println!("pt. 2: deleting {path} clearing {size}");
+    let path = smallest_deleteable_directory.metadata.path(fs);
+    let size = smallest_deleteable_directory.size(fs);
+    Ok(Solution::new(size).with_extra(format!("deleting {path}")))
+}
+
+pub fn solve_part1(input: &Path) -> Result<Solution, Error> {
+    part1(&parse(input)?)
+}
 
-    Ok(())
+pub fn solve_part2(input: &Path) -> Result<Solution, Error> {
+    part2(&parse(input)?)
+}
+
+/// Day 7: No Space Left On Device
+pub struct Day07;
+
+impl Puzzle for Day07 {
+    const DAY: u8 = 7;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const LABELS: [&'static str; 2] = ["size sum", "pt. 2: clearing"];
+
+    type Input = Filesystem;
+    type Params = NoParams;
+    type Answer1 = Solution;
+    type Answer2 = Solution;
+    type Error = Error;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_str(input)
+    }
+
+    fn part1(input: &Self::Input, _params: &NoParams) -> Result<Solution, Error> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input, _params: &NoParams) -> Result<Solution, Error> {
+        part2(input)
+    }
+
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn render(input: &Self::Input) -> String {
+        input.to_string()
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -282,4 +408,58 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("no solution found")]
     NoSolution,
+    #[error("used space ({0}) is greater than total disk space")]
+    DiskOverfull(u64),
+    #[error("parsing input")]
+    Parse(#[from] Diagnostic),
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::Parse(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::Answer;
+
+    #[test]
+    fn example_part1() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part1(&input).unwrap().answer, Answer::from(95437));
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part2(&input).unwrap().answer, Answer::from(24933642));
+    }
+
+    #[test]
+    fn part2_with_enough_space() {
+        let input = parse_str("$ cd /\n$ ls\n1000 small.txt\n").unwrap();
+        assert_eq!(part2(&input).unwrap().answer, Answer::from("nothing"));
+    }
+
+    #[test]
+    fn render_round_trips() {
+        common::puzzle::assert_round_trips::<Day07>(0..8, 20);
+    }
+
+    #[test]
+    fn rendering_canonicalizes_the_transcript() {
+        let input = "$ cd /\n$ ls\ndir a\n1 b.txt\n$ cd a\n$ ls\n2 c.txt\n$ cd /\n$ ls\n";
+        let fs = parse_str(input).unwrap();
+        let rendered = Day07::render(&fs);
+        assert_eq!(
+            rendered,
+            "$ cd /\n$ ls\ndir a\n1 b.txt\n$ cd a\n$ ls\n2 c.txt\n"
+        );
+        assert!(parse_str(&rendered).unwrap() == fs);
+    }
 }
@@ -0,0 +1,12 @@
+use rand::Rng;
+
+/// Generate a square grid of tree heights, `size` trees on a side.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+    let side = size.max(1);
+    let mut input = String::with_capacity((side + 1) * side);
+    for _ in 0..side {
+        input.extend((0..side).map(|_| char::from(rng.gen_range(b'0'..=b'9'))));
+        input.push('\n');
+    }
+    input
+}
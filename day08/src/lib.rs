@@ -2,7 +2,30 @@ use aoclib::geometry::{
     map::{tile::Digit, Map},
     Direction, Point,
 };
-use std::{collections::HashSet, path::Path};
+use common::{
+    diagnostic::{check_grid, parse_file, Diagnosed},
+    visualize::Hooks,
+    Diagnostic, Puzzle, Solution, VisualizeArgs,
+};
+use rand::Rng;
+use std::{io::Read, path::Path};
+use structopt::StructOpt;
+use visibility::Forest;
+
+mod generate;
+mod visibility;
+
+pub use generate::generate;
+
+/// The worked example from the puzzle description.
+pub const EXAMPLE: &str = include_str!("../example.txt");
+
+// Ways to watch the trees come into view.
+#[derive(Debug, Default, Clone, PartialEq, Eq, StructOpt)]
+pub struct Params {
+    #[structopt(flatten)]
+    pub visualize: VisualizeArgs,
+}
 
 /// Return the trees which are visible from this projection.
 ///
@@ -56,36 +79,107 @@ fn scenic_score(map: &Map<Digit>, origin: Point) -> usize {
         .product()
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let trees = <Map<Digit> as TryFrom<&Path>>::try_from(input)?;
-    let n_visible = Direction::iter()
-        .flat_map(|direction| trees.edge(direction).map(move |point| (direction, point)))
-        .flat_map(|(direction, edge_point)| {
+pub fn parse_str(input: &str) -> Result<Map<Digit>, Error> {
+    // each tree's height is a single digit
+    check_grid::<u8>(input)?;
+    Ok(<Map<Digit> as TryFrom<&str>>::try_from(input)?)
+}
+
+pub fn from_reader(mut reader: impl Read) -> Result<Map<Digit>, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_str(&input)
+}
+
+pub fn parse(input: &Path) -> Result<Map<Digit>, Error> {
+    parse_file(input, parse_str)
+}
+
+pub fn part1(trees: &Map<Digit>) -> Result<Solution, Error> {
+    part1_with(trees, &Params::default())
+}
+
+pub fn part1_with(trees: &Map<Digit>, params: &Params) -> Result<Solution, Error> {
+    let mut forest = Forest::new(trees);
+    let mut hooks = Hooks::pre(&forest, &params.visualize)?;
+
+    // look inwards along each row and column, from both ends
+    for direction in Direction::iter() {
+        for edge_point in trees.edge(direction) {
             let (dx, dy) = direction.reverse().deltas();
-            filter_visible(&trees, trees.project(edge_point, dx, dy))
-        })
-        .collect::<HashSet<_>>()
-        .len();
-    println!("n visible trees: {n_visible}");
-    Ok(())
+            for point in filter_visible(trees, trees.project(edge_point, dx, dy)) {
+                forest.see(point);
+            }
+            hooks.trace(&forest)?;
+        }
+    }
+
+    hooks.post(&forest)?;
+    Ok(Solution::new(forest.n_visible()))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(trees: &Map<Digit>) -> Result<Solution, Error> {
     // We can't re-use the result from part 1 to filter the points to consider here.
     // Consider a map whose perimeter trees all have height 9. They all have a scenic
     // score of 0, because there is at least one direction in which they can see no
     // other trees at all. However, they block all potential inner trees which might have a
     // higher score.
 
-    let trees = <Map<Digit> as TryFrom<&Path>>::try_from(input)?;
-    let Some(max_scenic_score) = trees.points().map(|point| scenic_score(&trees, point)).max() else {
-        println!("map has size 0");
-        return Ok(())
-    };
+    let max_scenic_score = trees
+        .points()
+        .map(|point| scenic_score(trees, point))
+        .max()
+        // the map has size 0
+        .ok_or(Error::NoSolution)?;
 
-    println!("max scenic score: {max_scenic_score}");
+    Ok(Solution::new(max_scenic_score))
+}
 
-    Ok(())
+pub fn solve_part1(input: &Path) -> Result<Solution, Error> {
+    part1(&parse(input)?)
+}
+
+pub fn solve_part2(input: &Path) -> Result<Solution, Error> {
+    part2(&parse(input)?)
+}
+
+pub fn solve_part1_with(input: &Path, params: &Params) -> Result<Solution, Error> {
+    part1_with(&parse(input)?, params)
+}
+
+/// Day 8: Treetop Tree House
+pub struct Day08;
+
+impl Puzzle for Day08 {
+    const DAY: u8 = 8;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const LABELS: [&'static str; 2] = ["n visible trees", "max scenic score"];
+
+    type Input = Map<Digit>;
+    type Params = Params;
+    type Answer1 = Solution;
+    type Answer2 = Solution;
+    type Error = Error;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_str(input)
+    }
+
+    fn part1(input: &Self::Input, params: &Params) -> Result<Solution, Error> {
+        part1_with(input, params)
+    }
+
+    fn part2(input: &Self::Input, _params: &Params) -> Result<Solution, Error> {
+        part2(input)
+    }
+
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn render(input: &Self::Input) -> String {
+        input.to_string()
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -94,4 +188,40 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("no solution found")]
     NoSolution,
+    #[error("visualizing")]
+    Visualize(#[from] common::visualize::Error),
+    #[error("parsing input")]
+    Parse(#[from] Diagnostic),
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::Parse(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::Answer;
+
+    #[test]
+    fn example_part1() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part1(&input).unwrap().answer, Answer::from(21));
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part2(&input).unwrap().answer, Answer::from(8));
+    }
+
+    #[test]
+    fn render_round_trips() {
+        common::puzzle::assert_round_trips::<Day08>(0..8, 20);
+    }
 }
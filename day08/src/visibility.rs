@@ -0,0 +1,80 @@
+//! Draw which trees can be seen from outside the grid.
+
+use aoclib::geometry::{
+    map::{tile::Digit, Map},
+    tile::{DisplayWidth, ToRgb},
+    Point,
+};
+use common::visualize::Draw;
+use std::{borrow::Cow, fmt};
+
+/// A tree, which is highlighted once it's known to be visible.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Tree {
+    height: u8,
+    visible: bool,
+}
+
+impl fmt::Display for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.visible {
+            write!(f, "{}", self.height)
+        } else {
+            write!(f, ".")
+        }
+    }
+}
+
+impl DisplayWidth for Tree {
+    const DISPLAY_WIDTH: usize = 1;
+}
+
+impl ToRgb for Tree {
+    fn to_rgb(&self) -> [u8; 3] {
+        // taller trees are brighter
+        let shade = 15 * self.height;
+        if self.visible {
+            [120 + shade, 120 + shade, 40]
+        } else {
+            [0, 30 + shade, 0]
+        }
+    }
+}
+
+/// The trees, and which of them have been seen so far.
+pub struct Forest {
+    map: Map<Tree>,
+}
+
+impl Forest {
+    pub fn new(trees: &Map<Digit>) -> Self {
+        let offset = Point::new(trees.low_x(), trees.low_y());
+        let mut map: Map<Tree> = Map::new_offset(offset, trees.width(), trees.height());
+        for (point, &height) in trees.iter() {
+            map[point].height = height.into();
+        }
+        Self { map }
+    }
+
+    pub fn see(&mut self, point: Point) {
+        self.map[point].visible = true;
+    }
+
+    pub fn n_visible(&self) -> usize {
+        self.map.iter().filter(|(_, tree)| tree.visible).count()
+    }
+}
+
+impl fmt::Display for Forest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.map.fmt(f)
+    }
+}
+
+impl Draw for Forest {
+    type Tile = Tree;
+
+    fn frame(&self) -> Cow<'_, Map<Tree>> {
+        Cow::Borrowed(&self.map)
+    }
+}
@@ -0,0 +1,115 @@
+//! Draw the rope as it moves, and the places its tail has been.
+
+use aoclib::geometry::{
+    tile::{DisplayWidth, ToRgb},
+    Direction, Map, Point,
+};
+use common::visualize::Draw;
+use std::{borrow::Cow, collections::HashSet, fmt};
+
+use crate::Rope;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    #[default]
+    Empty,
+    Visited,
+    Knot,
+    Head,
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            Cell::Empty => '.',
+            Cell::Visited => '#',
+            Cell::Knot => 'o',
+            Cell::Head => 'H',
+        };
+        write!(f, "{c}")
+    }
+}
+
+impl DisplayWidth for Cell {
+    const DISPLAY_WIDTH: usize = 1;
+}
+
+impl ToRgb for Cell {
+    fn to_rgb(&self) -> [u8; 3] {
+        match self {
+            Cell::Empty => [0, 0, 0],
+            Cell::Visited => [80, 80, 160],
+            Cell::Knot => [200, 160, 80],
+            Cell::Head => [255, 60, 60],
+        }
+    }
+}
+
+/// A rope, and everywhere its tail has visited.
+#[derive(Clone)]
+pub struct Bridge {
+    pub rope: Rope,
+    pub tail_visited: HashSet<Point>,
+    /// The `(min, max)` corners of every point the head will visit.
+    bounds: (Point, Point),
+}
+
+impl Bridge {
+    pub fn new(num_knots: usize, moves: impl IntoIterator<Item = Direction>) -> Self {
+        let rope = Rope::new(num_knots);
+        let tail_visited = HashSet::from([rope.tail()]);
+
+        // every knot follows the head, so never leaves the area the head covers
+        let mut head = Point::default();
+        let mut bounds = (head, head);
+        for direction in moves {
+            head += direction;
+            bounds.0.x = bounds.0.x.min(head.x);
+            bounds.0.y = bounds.0.y.min(head.y);
+            bounds.1.x = bounds.1.x.max(head.x);
+            bounds.1.y = bounds.1.y.max(head.y);
+        }
+
+        Self {
+            rope,
+            tail_visited,
+            bounds,
+        }
+    }
+
+    pub fn step(&mut self, direction: Direction) {
+        self.rope.step(direction);
+        self.tail_visited.insert(self.rope.tail());
+    }
+
+    fn map(&self) -> Map<Cell> {
+        let (min, max) = self.bounds;
+        let mut map = Map::new_offset(
+            min,
+            (max.x - min.x) as usize + 1,
+            (max.y - min.y) as usize + 1,
+        );
+        for &point in &self.tail_visited {
+            map[point] = Cell::Visited;
+        }
+        // draw the tail first so the knots nearer the head are drawn over it
+        for (idx, &knot) in self.rope.knots.iter().enumerate().rev() {
+            map[knot] = if idx == 0 { Cell::Head } else { Cell::Knot };
+        }
+        map
+    }
+}
+
+impl fmt::Display for Bridge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.map().fmt(f)
+    }
+}
+
+impl Draw for Bridge {
+    type Tile = Cell;
+
+    fn frame(&self) -> Cow<'_, Map<Cell>> {
+        Cow::Owned(self.map())
+    }
+}
@@ -0,0 +1,12 @@
+use rand::Rng;
+use std::fmt::Write;
+
+/// Generate `size` motions of the rope's head.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+    let mut input = String::new();
+    for _ in 0..size {
+        let direction = ['R', 'L', 'U', 'D'][rng.gen_range(0..4)];
+        writeln!(input, "{direction} {}", rng.gen_range(1..=20)).unwrap();
+    }
+    input
+}
@@ -1,12 +1,33 @@
-use aoclib::{
-    geometry::{Direction, Point},
-    parse,
+use aoclib::geometry::{Direction, Point};
+use bridge::Bridge;
+use common::{
+    diagnostic::{parse_file, parse_lines, Diagnosed},
+    input::join_lines,
+    repl::{Interactive, Simulation},
+    visualize::Hooks,
+    Diagnostic, Puzzle, Solution, VisualizeArgs,
 };
-use maplit::hashset;
 use parse_display::{Display, FromStr};
-use std::path::Path;
+use rand::Rng;
+use std::{fmt, io::Read, path::Path};
+use structopt::StructOpt;
 
-#[derive(Debug, Clone, Copy, FromStr, Display)]
+mod bridge;
+mod generate;
+
+pub use generate::generate;
+
+/// The worked example from the puzzle description.
+pub const EXAMPLE: &str = include_str!("../example.txt");
+
+// Ways to watch the rope move.
+#[derive(Debug, Default, Clone, PartialEq, Eq, StructOpt)]
+pub struct Params {
+    #[structopt(flatten)]
+    pub visualize: VisualizeArgs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromStr, Display)]
 enum InstructionDirection {
     #[display("R")]
     Right,
@@ -29,15 +50,15 @@ impl From<InstructionDirection> for Direction {
     }
 }
 
-#[derive(Debug, Clone, Copy, FromStr, Display)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromStr, Display)]
 #[display("{direction} {qty}")]
-struct Instruction {
+pub struct Instruction {
     direction: InstructionDirection,
     qty: usize,
 }
 
-#[derive(Default, Debug)]
-struct Rope {
+#[derive(Default, Debug, Clone)]
+pub(crate) struct Rope {
     knots: Vec<Point>,
 }
 
@@ -82,28 +103,163 @@ impl Rope {
     }
 }
 
-fn solve(input: &Path, part: u32, num_knots: usize) -> Result<(), Error> {
-    let mut rope = Rope::new(num_knots);
-    let mut tail_visited = hashset!(rope.tail());
+fn moves(instructions: &[Instruction]) -> impl '_ + Iterator<Item = Direction> {
+    instructions.iter().flat_map(|instruction| {
+        std::iter::repeat_n::<Direction>(instruction.direction.into(), instruction.qty)
+    })
+}
+
+fn solve(
+    instructions: &[Instruction],
+    num_knots: usize,
+    params: &Params,
+) -> Result<Solution, Error> {
+    let mut bridge = Bridge::new(num_knots, moves(instructions));
+    let mut hooks = Hooks::pre(&bridge, &params.visualize)?;
 
-    for direction in parse::<Instruction>(input)?.flat_map(|instruction| {
-        std::iter::repeat::<Direction>(instruction.direction.into()).take(instruction.qty)
-    }) {
-        rope.step(direction);
-        tail_visited.insert(rope.tail());
+    for direction in moves(instructions) {
+        bridge.step(direction);
+        hooks.trace(&bridge)?;
     }
 
-    println!("tail visited qty (pt. {part}): {}", tail_visited.len());
+    hooks.post(&bridge)?;
+    Ok(Solution::new(bridge.tail_visited.len()))
+}
+
+/// A rope partway through its moves.
+#[derive(Clone)]
+pub struct Walk {
+    bridge: Bridge,
+    moves: Vec<Direction>,
+    /// The index of the next move to make.
+    next: usize,
+}
+
+impl Simulation for Walk {
+    type Error = Error;
+
+    /// Move the head by one square.
+    fn step(&mut self) -> Result<bool, Error> {
+        let Some(&direction) = self.moves.get(self.next) else {
+            return Ok(false);
+        };
+        self.bridge.step(direction);
+        self.next += 1;
+        Ok(true)
+    }
+
+    fn probes(&self) -> Vec<(String, i64)> {
+        let knots = &self.bridge.rope.knots;
+        let head = knots.first().copied().unwrap_or_default();
+        let tail = self.bridge.rope.tail();
+        vec![
+            ("head_x".into(), head.x.into()),
+            ("head_y".into(), head.y.into()),
+            ("tail_x".into(), tail.x.into()),
+            ("tail_y".into(), tail.y.into()),
+            ("visited".into(), self.bridge.tail_visited.len() as i64),
+        ]
+    }
+}
+
+impl fmt::Display for Walk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.bridge.fmt(f)
+    }
+}
+
+pub fn parse_str(input: &str) -> Result<Vec<Instruction>, Error> {
+    Ok(parse_lines(input)?)
+}
+
+pub fn from_reader(mut reader: impl Read) -> Result<Vec<Instruction>, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_str(&input)
+}
+
+pub fn parse(input: &Path) -> Result<Vec<Instruction>, Error> {
+    parse_file(input, parse_str)
+}
+
+pub fn part1(instructions: &[Instruction]) -> Result<Solution, Error> {
+    part1_with(instructions, &Params::default())
+}
+
+pub fn part1_with(instructions: &[Instruction], params: &Params) -> Result<Solution, Error> {
+    solve(instructions, 2, params)
+}
+
+pub fn part2(instructions: &[Instruction]) -> Result<Solution, Error> {
+    part2_with(instructions, &Params::default())
+}
+
+pub fn part2_with(instructions: &[Instruction], params: &Params) -> Result<Solution, Error> {
+    solve(instructions, 10, params)
+}
+
+pub fn solve_part1(input: &Path) -> Result<Solution, Error> {
+    part1(&parse(input)?)
+}
 
-    Ok(())
+pub fn solve_part2(input: &Path) -> Result<Solution, Error> {
+    part2(&parse(input)?)
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    solve(input, 1, 2)
+pub fn solve_part1_with(input: &Path, params: &Params) -> Result<Solution, Error> {
+    part1_with(&parse(input)?, params)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    solve(input, 2, 10)
+pub fn solve_part2_with(input: &Path, params: &Params) -> Result<Solution, Error> {
+    part2_with(&parse(input)?, params)
+}
+
+/// Day 9: Rope Bridge
+pub struct Day09;
+
+impl Puzzle for Day09 {
+    const DAY: u8 = 9;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const LABELS: [&'static str; 2] = ["tail visited qty (pt. 1)", "tail visited qty (pt. 2)"];
+
+    type Input = Vec<Instruction>;
+    type Params = Params;
+    type Answer1 = Solution;
+    type Answer2 = Solution;
+    type Error = Error;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_str(input)
+    }
+
+    fn part1(input: &Self::Input, params: &Params) -> Result<Solution, Error> {
+        part1_with(input, params)
+    }
+
+    fn part2(input: &Self::Input, params: &Params) -> Result<Solution, Error> {
+        part2_with(input, params)
+    }
+
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn render(input: &Self::Input) -> String {
+        join_lines(input)
+    }
+}
+
+impl Interactive for Day09 {
+    type Simulation = Walk;
+
+    fn simulate(input: &Vec<Instruction>, part: u8) -> Walk {
+        let num_knots = if part == 1 { 2 } else { 10 };
+        Walk {
+            bridge: Bridge::new(num_knots, moves(input)),
+            moves: moves(input).collect(),
+            next: 0,
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -112,4 +268,40 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("no solution found")]
     NoSolution,
+    #[error("visualizing")]
+    Visualize(#[from] common::visualize::Error),
+    #[error("parsing input")]
+    Parse(#[from] Diagnostic),
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::Parse(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::Answer;
+
+    #[test]
+    fn example_part1() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part1(&input).unwrap().answer, Answer::from(13));
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part2(&input).unwrap().answer, Answer::from(1));
+    }
+
+    #[test]
+    fn render_round_trips() {
+        common::puzzle::assert_round_trips::<Day09>(0..8, 20);
+    }
 }
@@ -0,0 +1,30 @@
+use crate::Instruction;
+use rand::Rng;
+use std::fmt::Write;
+
+/// The number of cycles the CRT takes to draw a frame.
+const FRAME_CYCLES: i32 = 240;
+
+/// Generate a program which runs for `size` cycles.
+///
+/// The screen only has room for one frame, so programs run for at most 240 cycles.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+    let total_cycles = i32::try_from(size).map_or(FRAME_CYCLES, |size| size.min(FRAME_CYCLES));
+    let mut input = String::new();
+    let mut cycles = 0;
+    let mut register = 1;
+    while cycles < total_cycles {
+        let instruction = if total_cycles - cycles >= 2 && rng.gen_bool(0.6) {
+            // keep the sprite roughly on screen
+            let target = rng.gen_range(-1..=40);
+            let delta = (target - register).clamp(-20, 20);
+            register += delta;
+            Instruction::Addx(delta)
+        } else {
+            Instruction::Noop
+        };
+        cycles += instruction.cycles();
+        writeln!(input, "{instruction}").unwrap();
+    }
+    input
+}
@@ -1,15 +1,37 @@
-use aoclib::{
-    geometry::{tile::Bool, Map, Point},
-    parse,
+use aoclib::geometry::{tile::Bool, Map, Point};
+use common::{
+    diagnostic::{parse_file, parse_lines, Diagnosed},
+    input::join_lines,
+    repl::{Interactive, Simulation},
+    visualize::{Draw, Hooks},
+    Diagnostic, Puzzle, Solution, VisualizeArgs,
 };
 use parse_display::{Display, FromStr};
+use rand::Rng;
 use std::{
+    borrow::Cow,
     fmt,
+    io::Read,
     ops::{Index, IndexMut},
     path::Path,
 };
+use structopt::StructOpt;
 
-#[derive(Debug)]
+mod generate;
+
+pub use generate::generate;
+
+/// The worked example from the puzzle description.
+pub const EXAMPLE: &str = include_str!("../example.txt");
+
+// Ways to watch the CRT draw.
+#[derive(Debug, Default, Clone, PartialEq, Eq, StructOpt)]
+pub struct Params {
+    #[structopt(flatten)]
+    pub visualize: VisualizeArgs,
+}
+
+#[derive(Debug, Clone)]
 struct Cpu {
     program: Vec<Instruction>,
     register: i32,
@@ -53,7 +75,7 @@ impl Cpu {
         self.cycle_counter += 1;
     }
 
-    fn trace<'a, F, V>(&'a mut self, inspector: F) -> impl '_ + Iterator<Item = V>
+    fn trace<'a, F, V>(&'a mut self, inspector: F) -> impl 'a + Iterator<Item = V>
     where
         F: 'a + Fn(&Self) -> V,
     {
@@ -72,8 +94,8 @@ impl Cpu {
     }
 }
 
-#[derive(Default, Debug, Clone, Copy, FromStr, Display)]
-enum Instruction {
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, FromStr, Display)]
+pub enum Instruction {
     #[default]
     #[display("noop")]
     Noop,
@@ -98,6 +120,7 @@ fn filter_interesting<T>(iter: impl Iterator<Item = T>) -> impl Iterator<Item =
         .map(|(_idx, value)| value)
 }
 
+#[derive(Clone)]
 struct Screen {
     buffer: Map<Bool>,
 }
@@ -132,7 +155,23 @@ impl IndexMut<usize> for Screen {
     }
 }
 
-struct VideoSystem {
+impl fmt::Display for Screen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.buffer.flip_vertical().fmt(f)
+    }
+}
+
+impl Draw for Screen {
+    type Tile = Bool;
+
+    fn frame(&self) -> Cow<'_, Map<Bool>> {
+        Cow::Borrowed(&self.buffer)
+    }
+}
+
+/// A CPU driving a CRT.
+#[derive(Clone)]
+pub struct VideoSystem {
     cpu: Cpu,
     screen: Screen,
 }
@@ -145,51 +184,150 @@ impl VideoSystem {
         }
     }
 
-    fn scan(&mut self) {
+    /// Run a single cycle, drawing one pixel. `false` once the program has finished.
+    fn cycle(&mut self) -> bool {
         const SPRITE: [i32; 3] = [-1, 0, 1];
-        for (idx, x_register) in self
-            .cpu
-            .trace(|cpu| cpu.register)
-            // skip 1 because the first trace output comes from before cycle 1
-            .skip(1)
-            .enumerate()
+
+        // the CPU's state before its first tick comes from before cycle 1
+        if self.cpu.cycle_counter == 0 {
+            self.cpu.tick();
+        }
+        if self.cpu.cycle_counter > self.cpu.cycle_when_instruction_completes {
+            return false;
+        }
+
+        let idx = (self.cpu.cycle_counter - 1) as usize;
+        let x = self.screen.beam_position(idx).x;
+        if SPRITE
+            .iter()
+            .map(|offset| offset + self.cpu.register)
+            .any(|sprite| sprite == x)
         {
-            let x = self.screen.beam_position(idx).x;
-            if SPRITE
-                .iter()
-                .map(|offset| offset + x_register)
-                .any(|sprite| sprite == x)
-            {
-                self.screen[idx] = true.into()
-            }
+            self.screen[idx] = true.into()
         }
+        self.cpu.tick();
+        true
+    }
+
+    fn scan(&mut self, hooks: &mut Hooks) -> Result<(), Error> {
+        while self.cycle() {
+            hooks.trace(&self.screen)?;
+        }
+        Ok(())
+    }
+}
+
+impl Simulation for VideoSystem {
+    type Error = Error;
+
+    fn step(&mut self) -> Result<bool, Error> {
+        Ok(self.cycle())
+    }
+
+    fn probes(&self) -> Vec<(String, i64)> {
+        // these describe the cycle which is about to run
+        vec![
+            ("cycle".into(), self.cpu.cycle_counter.into()),
+            ("x".into(), self.cpu.register.into()),
+            ("signal".into(), self.cpu.signal_strength().into()),
+        ]
     }
 }
 
 impl fmt::Display for VideoSystem {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.screen.buffer.flip_vertical().fmt(f)
+        self.screen.fmt(f)
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let program: Vec<Instruction> = parse(input)?.collect();
-    let mut cpu = Cpu::new(program);
+pub fn parse_str(input: &str) -> Result<Vec<Instruction>, Error> {
+    Ok(parse_lines(input)?)
+}
+
+pub fn from_reader(mut reader: impl Read) -> Result<Vec<Instruction>, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_str(&input)
+}
+
+pub fn parse(input: &Path) -> Result<Vec<Instruction>, Error> {
+    parse_file(input, parse_str)
+}
+
+pub fn part1(program: &[Instruction]) -> Result<Solution, Error> {
+    let mut cpu = Cpu::new(program.to_vec());
     let signal_strength_sum: i32 =
         filter_interesting(cpu.trace(|cpu| cpu.signal_strength()).enumerate())
             .map(|(_idx, signal_strength)| signal_strength)
             .sum();
-    println!("sum of signal strength: {signal_strength_sum}");
+    Ok(Solution::new(signal_strength_sum))
+}
+
+pub fn part2(program: &[Instruction]) -> Result<Solution, Error> {
+    part2_with(program, &Params::default())
+}
+
+pub fn part2_with(program: &[Instruction], params: &Params) -> Result<Solution, Error> {
+    let mut video_system = VideoSystem::new(program.to_vec());
+    let mut hooks = Hooks::pre(&video_system.screen, &params.visualize)?;
+    video_system.scan(&mut hooks)?;
+    hooks.post(&video_system.screen)?;
+    Ok(Solution::grid(video_system))
+}
+
+pub fn solve_part1(input: &Path) -> Result<Solution, Error> {
+    part1(&parse(input)?)
+}
 
-    Ok(())
+pub fn solve_part2(input: &Path) -> Result<Solution, Error> {
+    part2(&parse(input)?)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let program: Vec<Instruction> = parse(input)?.collect();
-    let mut video_system = VideoSystem::new(program);
-    video_system.scan();
-    println!("video system shows:\n{video_system}");
-    Ok(())
+pub fn solve_part2_with(input: &Path, params: &Params) -> Result<Solution, Error> {
+    part2_with(&parse(input)?, params)
+}
+
+/// Day 10: Cathode-Ray Tube
+pub struct Day10;
+
+impl Puzzle for Day10 {
+    const DAY: u8 = 10;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const LABELS: [&'static str; 2] = ["sum of signal strength", "video system shows"];
+
+    type Input = Vec<Instruction>;
+    type Params = Params;
+    type Answer1 = Solution;
+    type Answer2 = Solution;
+    type Error = Error;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_str(input)
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Result<Solution, Error> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input, params: &Params) -> Result<Solution, Error> {
+        part2_with(input, params)
+    }
+
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn render(input: &Self::Input) -> String {
+        join_lines(input)
+    }
+}
+
+impl Interactive for Day10 {
+    type Simulation = VideoSystem;
+
+    fn simulate(input: &Vec<Instruction>, _part: u8) -> VideoSystem {
+        VideoSystem::new(input.clone())
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -198,4 +336,55 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("no solution found")]
     NoSolution,
+    #[error("visualizing")]
+    Visualize(#[from] common::visualize::Error),
+    #[error("parsing input")]
+    Parse(#[from] Diagnostic),
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::Parse(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::Answer;
+
+    #[test]
+    fn example_part1() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part1(&input).unwrap().answer, Answer::from(13140));
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = parse_str(EXAMPLE).unwrap();
+        let expect = "##..##..##..##..##..##..##..##..##..##..\n\
+             ###...###...###...###...###...###...###.\n\
+             ####....####....####....####....####....\n\
+             #####.....#####.....#####.....#####.....\n\
+             ######......######......######......####\n\
+             #######.......#######.......#######.....\n";
+        assert_eq!(part2(&input).unwrap().answer, Answer::Grid(expect.into()));
+    }
+
+    #[test]
+    fn render_round_trips() {
+        common::puzzle::assert_round_trips::<Day10>(0..8, 20);
+    }
+
+    #[test]
+    fn generated_programs_run_for_size_cycles() {
+        for (size, expect) in [(0, 0), (20, 20), (100, 100), (1000, 240)] {
+            let program = parse_str(&common::puzzle::generate_seeded::<Day10>(0, size)).unwrap();
+            let cycles: i32 = program.iter().map(|instruction| instruction.cycles()).sum();
+            assert_eq!(cycles, expect, "size {size}");
+        }
+    }
 }
@@ -0,0 +1,134 @@
+use rand::{seq::SliceRandom, Rng};
+use std::fmt::{self, Write};
+
+const PRIMES: [u64; 9] = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Add(u64),
+    Mul(u64),
+    Square,
+}
+
+impl Op {
+    fn checked_perform(self, old: u64) -> Option<u64> {
+        match self {
+            Op::Add(value) => old.checked_add(value),
+            Op::Mul(value) => old.checked_mul(value),
+            Op::Square => old.checked_mul(old),
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Op::Add(value) => write!(f, "old + {value}"),
+            Op::Mul(value) => write!(f, "old * {value}"),
+            Op::Square => write!(f, "old * old"),
+        }
+    }
+}
+
+struct Spec {
+    items: Vec<u64>,
+    op: Op,
+    divisor: u64,
+    if_true: usize,
+    if_false: usize,
+}
+
+/// Play part 1, returning the index of the first monkey whose operation overflows, if any.
+///
+/// Part 2 can't overflow: worry is kept below the product of distinct primes, whose square fits.
+fn first_overflow(specs: &[Spec]) -> Option<usize> {
+    let mut items = specs
+        .iter()
+        .map(|spec| spec.items.clone())
+        .collect::<Vec<_>>();
+    for _ in 0..20 {
+        for (idx, spec) in specs.iter().enumerate() {
+            for worry in std::mem::take(&mut items[idx]) {
+                let worry = match spec.op.checked_perform(worry) {
+                    Some(worry) => worry / 3,
+                    None => return Some(idx),
+                };
+                let destination = if worry % spec.divisor == 0 {
+                    spec.if_true
+                } else {
+                    spec.if_false
+                };
+                items[destination].push(worry);
+            }
+        }
+    }
+    None
+}
+
+/// Generate `size` monkeys, or at least three.
+///
+/// Operations which would overflow part 1 are replaced with additions until none do. Additions
+/// alone never overflow, since dividing by three outpaces them.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+    let n_monkeys = size.max(3);
+    let mut primes = PRIMES;
+    primes.shuffle(rng);
+
+    let mut specs = (0..n_monkeys)
+        .map(|idx| {
+            let op = match rng.gen_range(0..10) {
+                0..=4 => Op::Add(rng.gen_range(1..=8)),
+                5..=8 => Op::Mul(rng.gen_range(2..=19)),
+                _ => Op::Square,
+            };
+            let others = (0..n_monkeys).filter(|&other| other != idx);
+            let if_true = others.clone().nth(rng.gen_range(0..n_monkeys - 1)).unwrap();
+            let if_false = others
+                .filter(|&other| other != if_true)
+                .nth(rng.gen_range(0..n_monkeys - 2))
+                .unwrap();
+            Spec {
+                items: (0..rng.gen_range(1..=8))
+                    .map(|_| rng.gen_range(50..=99))
+                    .collect(),
+                op,
+                divisor: primes[idx % primes.len()],
+                if_true,
+                if_false,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    while let Some(idx) = first_overflow(&specs) {
+        // an addition only overflows on worry which something else grew, so tame everything else
+        let tame = match specs[idx].op {
+            Op::Add(_) => (0..n_monkeys).collect(),
+            _ => vec![idx],
+        };
+        for idx in tame {
+            if !matches!(specs[idx].op, Op::Add(_)) {
+                specs[idx].op = Op::Add(rng.gen_range(1..=8));
+            }
+        }
+    }
+
+    let mut input = String::new();
+    for (idx, spec) in specs.iter().enumerate() {
+        if idx > 0 {
+            input.push('\n');
+        }
+        let items = spec
+            .items
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        writeln!(input, "Monkey {idx}:").unwrap();
+        writeln!(input, "  Starting items: {items}").unwrap();
+        writeln!(input, "  Operation: new = {}", spec.op).unwrap();
+        writeln!(input, "  Test: divisible by {}", spec.divisor).unwrap();
+        writeln!(input, "    If true: throw to monkey {}", spec.if_true).unwrap();
+        writeln!(input, "    If false: throw to monkey {}", spec.if_false).unwrap();
+    }
+    input
+}
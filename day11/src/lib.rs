@@ -1,17 +1,41 @@
-use std::{ffi::OsStr, path::Path};
+use std::{fmt, io::Read, path::Path};
 
-use crate::troop::Troop;
+use common::{
+    diagnostic::{parse_file, Diagnosed},
+    input::join_blocks,
+    puzzle::NoParams,
+    repl::{Interactive, Simulation},
+    Diagnostic, Puzzle, Solution,
+};
+use rand::Rng;
 
+use crate::{models::Monkey, troop::Troop};
+pub use generate::generate;
+
+mod generate;
 mod models;
 mod parse;
 mod troop;
 
-pub(crate) fn env_is_set(key: impl AsRef<OsStr>) -> bool {
-    !std::env::var(key).unwrap_or_default().is_empty()
+/// The worked example from the puzzle description.
+pub const EXAMPLE: &str = include_str!("../example.txt");
+
+pub fn parse_str(input: &str) -> Result<Vec<Monkey>, Error> {
+    parse::parse_str(input)
+}
+
+pub fn from_reader(mut reader: impl Read) -> Result<Vec<Monkey>, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_str(&input)
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let mut troop = Troop::new(parse::parse(input)?, true);
+pub fn parse(input: &Path) -> Result<Vec<Monkey>, Error> {
+    parse_file(input, parse_str)
+}
+
+pub fn part1(monkeys: &[Monkey]) -> Result<Solution, Error> {
+    let mut troop = Troop::new(monkeys.to_vec(), true);
     for _ in 0..20 {
         troop.round();
     }
@@ -20,40 +44,131 @@ pub fn part1(input: &Path) -> Result<(), Error> {
         .into_iter()
         .map(|monkey| monkey.inspect_count)
         .product();
-    println!("monkey business (pt. 1): {monkey_business}");
-    Ok(())
+    Ok(Solution::new(monkey_business))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let mut troop = Troop::new(parse::parse(input)?, false);
-    for round in 0..10_000 {
-        if env_is_set("INSPECTION_SUMMARIES") && (round == 1 || round == 20 || round % 1000 == 0) {
-            eprintln!("After round {round}:");
+pub fn part2(monkeys: &[Monkey]) -> Result<Solution, Error> {
+    let mut troop = Troop::new(monkeys.to_vec(), false);
+    for round in 1..=10_000 {
+        troop.round();
+        if round == 1 || round == 20 || round % 1000 == 0 {
             for monkey in troop.iter() {
-                eprintln!(
-                    "Monkey {} inspected items {} times",
-                    monkey.id.0, monkey.inspect_count
+                tracing::debug!(
+                    round,
+                    monkey = monkey.id.0,
+                    inspections = monkey.inspect_count,
+                    "inspection summary"
                 );
             }
         }
-        troop.round();
-    }
-    if env_is_set("INSPECTION_SUMMARIES") {
-        eprintln!("After round 10000:");
-        for monkey in troop.iter() {
-            eprintln!(
-                "Monkey {} inspected items {} times",
-                monkey.id.0, monkey.inspect_count
-            );
-        }
     }
     let monkey_business: u64 = troop
         .active_monkeys(2)
         .into_iter()
         .map(|monkey| monkey.inspect_count as u64)
         .product();
-    println!("monkey business (pt. 2): {monkey_business}");
-    Ok(())
+    Ok(Solution::new(monkey_business))
+}
+
+/// A troop of monkeys playing a fixed number of rounds.
+#[derive(Clone)]
+pub struct Game {
+    troop: Troop,
+    rounds: usize,
+}
+
+impl Simulation for Game {
+    type Error = Error;
+
+    /// Play a round.
+    fn step(&mut self) -> Result<bool, Error> {
+        if self.troop.rounds() >= self.rounds {
+            return Ok(false);
+        }
+        self.troop.round();
+        Ok(true)
+    }
+
+    fn probes(&self) -> Vec<(String, i64)> {
+        let mut probes = vec![
+            ("round".to_owned(), self.troop.rounds() as i64),
+            (
+                "business".to_owned(),
+                self.troop.monkey_business().try_into().unwrap_or(i64::MAX),
+            ),
+        ];
+        probes.extend(
+            self.troop
+                .iter()
+                .map(|monkey| (format!("monkey{}", monkey.id), monkey.inspect_count.into())),
+        );
+        probes
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.troop.fmt(f)
+    }
+}
+
+pub fn solve_part1(input: &Path) -> Result<Solution, Error> {
+    part1(&parse(input)?)
+}
+
+pub fn solve_part2(input: &Path) -> Result<Solution, Error> {
+    part2(&parse(input)?)
+}
+
+/// Day 11: Monkey in the Middle
+pub struct Day11;
+
+impl Puzzle for Day11 {
+    const DAY: u8 = 11;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const LABELS: [&'static str; 2] = ["monkey business (pt. 1)", "monkey business (pt. 2)"];
+
+    type Input = Vec<Monkey>;
+    type Params = NoParams;
+    type Answer1 = Solution;
+    type Answer2 = Solution;
+    type Error = Error;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_str(input)
+    }
+
+    fn part1(input: &Self::Input, _params: &NoParams) -> Result<Solution, Error> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input, _params: &NoParams) -> Result<Solution, Error> {
+        part2(input)
+    }
+
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn render(input: &Self::Input) -> String {
+        join_blocks(input)
+    }
+}
+
+impl Interactive for Day11 {
+    type Simulation = Game;
+
+    fn simulate(input: &Vec<Monkey>, part: u8) -> Game {
+        let (part_one, rounds) = if part == 1 {
+            (true, 20)
+        } else {
+            (false, 10_000)
+        };
+        Game {
+            troop: Troop::new(input.clone(), part_one),
+            rounds,
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -68,4 +183,38 @@ pub enum Error {
     MonkeyBuilder(#[from] models::MonkeyBuilderError),
     #[error("malformed input: misplaced monkey. Expected monkey {0}, got {1}")]
     MisplacedMonkey(usize, usize),
+    #[error("parsing input")]
+    Parse(#[from] Diagnostic),
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::Parse(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::Answer;
+
+    #[test]
+    fn example_part1() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part1(&input).unwrap().answer, Answer::from(10605));
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part2(&input).unwrap().answer, Answer::from(2713310158_u64));
+    }
+
+    #[test]
+    fn render_round_trips() {
+        common::puzzle::assert_round_trips::<Day11>(0..8, 20);
+    }
 }
@@ -60,7 +60,7 @@ pub struct Test {
     pub divisible_by: u64,
 }
 
-#[derive(Debug, Builder)]
+#[derive(Debug, Clone, PartialEq, Eq, Builder)]
 #[builder(pattern = "owned")]
 pub struct Monkey {
     pub id: MonkeyId,
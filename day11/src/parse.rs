@@ -1,5 +1,6 @@
-use std::{collections::VecDeque, path::Path};
+use std::{collections::VecDeque, fmt};
 
+use common::{diagnostic::lines, Diagnostic};
 use parse_display::{Display, FromStr};
 
 use crate::{
@@ -32,7 +33,33 @@ enum InputLine {
     Blank,
 }
 
-pub fn parse(input: &Path) -> Result<Vec<Monkey>, Error> {
+impl fmt::Display for Monkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let items = self
+            .items
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        writeln!(f, "{}", InputLine::Monkey(self.id))?;
+        writeln!(f, "{}", InputLine::StartingItems(items))?;
+        writeln!(f, "  {}", InputLine::Operation(self.operation))?;
+        writeln!(f, "  {}", InputLine::Test(self.test))?;
+        for (output, destination) in [
+            (true, self.true_destination),
+            (false, self.false_destination),
+        ] {
+            let condition_output = ConditionOutput {
+                output,
+                destination,
+            };
+            writeln!(f, "    {}", InputLine::ConditionOutput(condition_output))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn parse_str(input: &str) -> Result<Vec<Monkey>, Error> {
     fn set_or_err(
         builder: &mut Option<MonkeyBuilder>,
         update: impl FnOnce(MonkeyBuilder) -> MonkeyBuilder,
@@ -54,56 +81,62 @@ pub fn parse(input: &Path) -> Result<Vec<Monkey>, Error> {
         Ok(())
     }
 
-    use aoclib::parse;
-
     let mut monkeys = Vec::new();
     let mut builder: Option<MonkeyBuilder> = None;
+    let mut last_line = input;
+
+    for line in lines(input) {
+        let located = |err: Error| Diagnostic::new(input, line, err);
+        last_line = line;
 
-    for line in parse::<InputLine>(input)? {
-        match line {
+        let parsed = line
+            .parse::<InputLine>()
+            .map_err(|err| Diagnostic::new(input, line, format!("unrecognized line: {err}")))?;
+        match parsed {
             InputLine::Monkey(id) => {
                 let result = build_monkey(&mut builder, &mut monkeys);
                 // we don't have a proper builder for the very first monkey
                 if !(id.0 == 0 && monkeys.is_empty() && result.is_err()) {
-                    result?;
+                    result.map_err(located)?;
                 }
                 builder = Some(MonkeyBuilder::default().id(id));
             }
             InputLine::StartingItems(items_str) => {
                 let mut items = VecDeque::new();
-                for substr in items_str.split(',') {
+                for substr in line[line.len() - items_str.len()..].split(',') {
                     let item: u64 = substr.trim().parse().map_err(|_| {
-                        std::io::Error::new(
-                            std::io::ErrorKind::Other,
-                            "could not parse monkey items",
-                        )
+                        Diagnostic::new(input, substr.trim(), "could not parse monkey item")
                     })?;
                     items.push_back(item);
                 }
 
-                set_or_err(&mut builder, |builder| builder.items(items))?;
+                set_or_err(&mut builder, |builder| builder.items(items)).map_err(located)?;
             }
             InputLine::Operation(operation) => {
-                set_or_err(&mut builder, |builder| builder.operation(operation))?;
+                set_or_err(&mut builder, |builder| builder.operation(operation))
+                    .map_err(located)?;
             }
             InputLine::Test(test) => {
-                set_or_err(&mut builder, |builder| builder.test(test))?;
+                set_or_err(&mut builder, |builder| builder.test(test)).map_err(located)?;
             }
             InputLine::ConditionOutput(condition_output) => {
                 if condition_output.output {
                     set_or_err(&mut builder, |builder| {
                         builder.true_destination(condition_output.destination)
-                    })?;
+                    })
+                    .map_err(located)?;
                 } else {
                     set_or_err(&mut builder, |builder| {
                         builder.false_destination(condition_output.destination)
-                    })?;
+                    })
+                    .map_err(located)?;
                 }
             }
             InputLine::Blank => {}
         }
     }
-    build_monkey(&mut builder, &mut monkeys)?;
+    build_monkey(&mut builder, &mut monkeys)
+        .map_err(|err| Diagnostic::new(input, last_line, err))?;
 
     Ok(monkeys)
 }
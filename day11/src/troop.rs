@@ -1,13 +1,17 @@
-use crate::env_is_set;
+use std::fmt;
+
 use crate::models::Monkey;
 
 fn least_common_multiple_many(of: impl IntoIterator<Item = u64>) -> Option<u64> {
     of.into_iter().reduce(num_integer::lcm)
 }
 
+#[derive(Clone)]
 pub struct Troop {
     monkeys: Vec<Monkey>,
     test_lcm: Option<u64>,
+    /// The number of rounds played so far.
+    rounds: usize,
 }
 
 impl Troop {
@@ -16,13 +20,32 @@ impl Troop {
             least_common_multiple_many(monkeys.iter().map(|monkey| monkey.test.divisible_by))
                 .unwrap_or(1)
         });
-        Self { monkeys, test_lcm }
+        Self {
+            monkeys,
+            test_lcm,
+            rounds: 0,
+        }
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &Monkey> {
         self.monkeys.iter()
     }
 
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    /// The product of the two highest inspection counts.
+    pub fn monkey_business(&self) -> u64 {
+        let mut counts = self
+            .monkeys
+            .iter()
+            .map(|monkey| monkey.inspect_count as u64)
+            .collect::<Vec<_>>();
+        counts.sort_unstable_by_key(|&count| std::cmp::Reverse(count));
+        counts.iter().take(2).product()
+    }
+
     fn turn_for(&mut self, monkey_idx: usize) {
         macro_rules! monkey {
             ($idx:expr) => {
@@ -39,54 +62,43 @@ impl Troop {
             };
         }
 
-        if env_is_set("MONKEY_TRACE") {
-            eprintln!("Monkey {monkey_idx}:");
-        }
+        let _turn = tracing::trace_span!("turn", monkey = monkey_idx).entered();
 
         while let Some(mut item_worry) = monkey_mut!(monkey_idx).items.pop_front() {
             monkey_mut!(monkey_idx).inspect_count += 1;
-
-            if env_is_set("MONKEY_TRACE") {
-                eprintln!("  Monkey inspects an item with a worry level of {item_worry}.");
-            }
+            tracing::trace!(worry = item_worry, "monkey inspects an item");
 
             let monkey = monkey!(monkey_idx);
             item_worry = monkey.operation.perform(item_worry);
-            if env_is_set("MONKEY_TRACE") {
-                eprintln!("    Worry level increases to {item_worry}.");
-            }
+            tracing::trace!(worry = item_worry, "worry level increases");
 
             if let Some(lcm) = self.test_lcm {
                 item_worry %= lcm;
             } else {
                 item_worry /= 3;
-                if env_is_set("MONKEY_TRACE") {
-                    eprintln!("    Monkey gets bored with item. Worry reduced to {item_worry}.");
-                }
+                tracing::trace!(worry = item_worry, "monkey gets bored with item");
             }
 
-            let divisibility;
-            let destination = if item_worry % monkey.test.divisible_by == 0 {
-                divisibility = "is";
+            let divisible = item_worry % monkey.test.divisible_by == 0;
+            let destination = if divisible {
                 monkey.true_destination.0
             } else {
-                divisibility = "is not";
                 monkey.false_destination.0
             };
-            if env_is_set("MONKEY_TRACE") {
-                eprintln!(
-                    "    Current worry level {divisibility} divisible by {}.",
-                    monkey.test.divisible_by
-                );
-                eprintln!(
-                    "    Item with worry level {item_worry} is thrown to monkey {destination}."
-                );
-            }
+            tracing::trace!(
+                worry = item_worry,
+                divisible_by = monkey.test.divisible_by,
+                divisible,
+                destination,
+                "item is thrown"
+            );
             monkey_mut!(destination).items.push_back(item_worry);
         }
     }
 
     pub fn round(&mut self) {
+        self.rounds += 1;
+        let _round = tracing::debug_span!("round", round = self.rounds).entered();
         for idx in 0..self.monkeys.len() {
             self.turn_for(idx);
         }
@@ -99,3 +111,24 @@ impl Troop {
         monkeys
     }
 }
+
+/// Each monkey's items and inspection count, as the puzzle describes them between rounds.
+impl fmt::Display for Troop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "After round {}:", self.rounds)?;
+        for monkey in &self.monkeys {
+            let items = monkey
+                .items
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(
+                f,
+                "Monkey {}: {items} (inspected items {} times)",
+                monkey.id, monkey.inspect_count
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 use aoclib::geometry::tile::DisplayWidth;
 
@@ -20,12 +20,28 @@ impl FromStr for Elevation {
         match b[0] {
             b'S' => Ok(Elevation::Start),
             b'E' => Ok(Elevation::Target),
-            _ if (b'a'..=b'z').contains(&b[0]) => Ok(Elevation::Height(b[0] - b'a')),
+            _ if b[0].is_ascii_lowercase() => Ok(Elevation::Height(b[0] - b'a')),
             _ => Err(ElevationParseError::OutOfBounds),
         }
     }
 }
 
+impl Default for Elevation {
+    fn default() -> Self {
+        Elevation::Height(0)
+    }
+}
+
+impl fmt::Display for Elevation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Elevation::Height(height) => write!(f, "{}", (b'a' + height) as char),
+            Elevation::Start => write!(f, "S"),
+            Elevation::Target => write!(f, "E"),
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ElevationParseError {
     #[error("wrong input width: have {0}; expected 1")]
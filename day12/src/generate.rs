@@ -0,0 +1,54 @@
+use rand::{seq::SliceRandom, Rng};
+
+const TOP: i32 = 25;
+
+/// Generate a square heightmap, `size` squares on a side, or at least 30.
+///
+/// Heights are the highest of several peaks which slope down by one per square, so no step ever
+/// climbs more than one; the target sits on the tallest peak, so every square can reach it.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+    let side = size.max(30) as i32;
+    loop {
+        let mut peaks = vec![(rng.gen_range(0..side), rng.gen_range(0..side), TOP)];
+        peaks.extend((0..side / 10).map(|_| {
+            (
+                rng.gen_range(0..side),
+                rng.gen_range(0..side),
+                rng.gen_range(5..TOP),
+            )
+        }));
+        let height = |x: i32, y: i32| {
+            peaks
+                .iter()
+                .map(|&(px, py, top)| top - (x - px).abs() - (y - py).abs())
+                .max()
+                .unwrap()
+                .clamp(0, TOP)
+        };
+
+        let lowlands = (0..side)
+            .flat_map(|y| (0..side).map(move |x| (x, y)))
+            .filter(|&(x, y)| height(x, y) == 0)
+            .collect::<Vec<_>>();
+        // the other peaks may have covered the lowlands; try again
+        let Some(&start) = lowlands.choose(rng) else {
+            continue;
+        };
+        let target = (peaks[0].0, peaks[0].1);
+
+        let mut input = String::with_capacity(((side + 1) * side) as usize);
+        for y in 0..side {
+            for x in 0..side {
+                input.push(if (x, y) == start {
+                    'S'
+                } else if (x, y) == target {
+                    'E'
+                } else {
+                    char::from(b'a' + height(x, y) as u8)
+                });
+            }
+            input.push('\n');
+        }
+        return input;
+    }
+}
@@ -1,12 +1,18 @@
 use std::{
+    fmt,
     ops::{Deref, Index},
     path::Path,
 };
 
 use aoclib::geometry::{Map, Point};
+use common::{
+    diagnostic::{check_grid, parse_file, Diagnosed},
+    Diagnostic,
+};
 
 use crate::elevation::{Elevation, ElevationParseError};
 
+#[derive(PartialEq, Eq)]
 pub struct HeightMap {
     map: Map<u8>,
     pub start: Point,
@@ -15,7 +21,19 @@ pub struct HeightMap {
 
 impl HeightMap {
     pub fn new(input: &Path) -> Result<Self, Error> {
-        let elevation_map = <Map<Elevation> as TryFrom<&Path>>::try_from(input)?;
+        parse_file(input, Self::parse_str)
+    }
+
+    pub fn parse_str(input: &str) -> Result<Self, Error> {
+        check_grid::<Elevation>(input)?;
+        for marker in ['S', 'E'] {
+            if let Some((idx, _)) = input.match_indices(marker).nth(1) {
+                let message = format!("more than one '{marker}' tile");
+                return Err(Diagnostic::new(input, &input[idx..idx + 1], message).into());
+            }
+        }
+
+        let elevation_map = <Map<Elevation> as TryFrom<&str>>::try_from(input)?;
 
         let mut map = Map::<u8>::new(elevation_map.width(), elevation_map.height());
         let mut start = None;
@@ -49,6 +67,18 @@ impl HeightMap {
     }
 }
 
+impl fmt::Display for HeightMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut elevation_map = Map::<Elevation>::new(self.map.width(), self.map.height());
+        for (location, &height) in self.map.iter() {
+            elevation_map[location] = Elevation::Height(height);
+        }
+        elevation_map[self.start] = Elevation::Start;
+        elevation_map[self.target] = Elevation::Target;
+        write!(f, "{elevation_map}")
+    }
+}
+
 impl Deref for HeightMap {
     type Target = Map<u8>;
 
@@ -75,4 +105,15 @@ pub enum Error {
     WrongNumberStartOrTarget,
     #[error("reading input")]
     Io(#[from] std::io::Error),
+    #[error("parsing input")]
+    Parse(#[from] Diagnostic),
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::Parse(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
 }
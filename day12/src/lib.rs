@@ -1,41 +1,55 @@
 mod elevation;
+mod generate;
 mod height_map;
 mod path_node;
+mod search;
 
 use aoclib::geometry::Point;
-use height_map::HeightMap;
+use common::{
+    diagnostic::Diagnosed, visualize::Hooks, Diagnostic, Puzzle, Solution, VisualizeArgs,
+};
+pub use generate::generate;
+pub use height_map::HeightMap;
 use path_node::PathNode;
+use rand::Rng;
+use search::Search;
+use structopt::StructOpt;
 
-use std::{
-    collections::{HashSet, VecDeque},
-    path::Path,
-    rc::Rc,
-};
+use std::{io::Read, path::Path, rc::Rc};
+
+/// The worked example from the puzzle description.
+pub const EXAMPLE: &str = include_str!("../example.txt");
+
+// Ways to watch the search spread.
+#[derive(Debug, Default, Clone, PartialEq, Eq, StructOpt)]
+pub struct Params {
+    #[structopt(flatten)]
+    pub visualize: VisualizeArgs,
+}
 
 fn find_path(
     map: &HeightMap,
     initial: Point,
     is_goal: impl Fn(&Rc<PathNode>) -> bool,
     can_step: impl Fn(Point, Point) -> bool,
-) -> Option<Rc<PathNode>> {
-    let mut visited = HashSet::new();
-    let mut queue = VecDeque::new();
-    queue.push_back(Rc::new(PathNode {
-        location: initial,
-        prev: None,
-    }));
-
-    while let Some(node) = queue.pop_front() {
+    params: &Params,
+) -> Result<Option<Rc<PathNode>>, Error> {
+    let mut search = Search::new(map, initial);
+    let mut hooks = Hooks::pre(&search, &params.visualize)?;
+
+    while let Some(node) = search.queue.pop_front() {
         if is_goal(&node) {
-            return Some(node);
+            search.path = Some(node);
+            break;
         }
 
-        if !visited.insert(node.location) {
+        if !search.visited.insert(node.location) {
             // we've already visited this location
             continue;
         }
 
-        queue.extend(
+        let visited = &search.visited;
+        search.queue.extend(
             map.adjacencies(node.location)
                 .filter(|&location| {
                     // we can't travel diagonally
@@ -51,47 +65,117 @@ fn find_path(
                         prev: Some(node.clone()),
                     })
                 }),
-        )
+        );
+        hooks.trace(&search)?;
     }
 
-    None
+    hooks.post(&search)?;
+    Ok(search.path)
 }
 
-fn solve(
-    input: &Path,
-    display: &str,
-    find_path: impl Fn(&HeightMap) -> Option<Rc<PathNode>>,
-) -> Result<(), Error> {
-    let map = HeightMap::new(input)?;
-    let path_to_destination = find_path(&map).ok_or(Error::NoSolution)?;
+fn solve(path_to_destination: Option<Rc<PathNode>>) -> Result<Solution, Error> {
+    let path_to_destination = path_to_destination.ok_or(Error::NoSolution)?;
     let node_count = path_to_destination.iter().count();
     // we can't parse a map on which the start and target positions are identical, so we know that there
     // are at least two nodes in every correct solution.
     let step_count = node_count - 1;
-    println!("{display}: {step_count}");
-    Ok(())
+    Ok(Solution::new(step_count))
+}
+
+pub fn parse_str(input: &str) -> Result<HeightMap, Error> {
+    Ok(HeightMap::parse_str(input)?)
+}
+
+pub fn from_reader(mut reader: impl Read) -> Result<HeightMap, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_str(&input)
+}
+
+pub fn parse(input: &Path) -> Result<HeightMap, Error> {
+    Ok(HeightMap::new(input)?)
+}
+
+pub fn part1(map: &HeightMap) -> Result<Solution, Error> {
+    part1_with(map, &Params::default())
+}
+
+pub fn part1_with(map: &HeightMap, params: &Params) -> Result<Solution, Error> {
+    solve(find_path(
+        map,
+        map.start,
+        |node| node.location == map.target,
+        |from, to| map[to] <= map[from] + 1,
+        params,
+    )?)
+}
+
+pub fn part2(map: &HeightMap) -> Result<Solution, Error> {
+    part2_with(map, &Params::default())
+}
+
+pub fn part2_with(map: &HeightMap, params: &Params) -> Result<Solution, Error> {
+    solve(find_path(
+        map,
+        map.target,
+        |node| map[node.location] == 0,
+        |from, to| map[to] >= map[from] - 1,
+        params,
+    )?)
+}
+
+pub fn solve_part1(input: &Path) -> Result<Solution, Error> {
+    part1(&parse(input)?)
+}
+
+pub fn solve_part2(input: &Path) -> Result<Solution, Error> {
+    part2(&parse(input)?)
+}
+
+pub fn solve_part1_with(input: &Path, params: &Params) -> Result<Solution, Error> {
+    part1_with(&parse(input)?, params)
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    solve(input, "1: steps in shortest path", |map| {
-        find_path(
-            map,
-            map.start,
-            |node| node.location == map.target,
-            |from, to| map[to] <= map[from] + 1,
-        )
-    })
+pub fn solve_part2_with(input: &Path, params: &Params) -> Result<Solution, Error> {
+    part2_with(&parse(input)?, params)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    solve(input, "2: steps in shortest possible path", |map| {
-        find_path(
-            map,
-            map.target,
-            |node| map[node.location] == 0,
-            |from, to| map[to] >= map[from] - 1,
-        )
-    })
+/// Day 12: Hill Climbing Algorithm
+pub struct Day12;
+
+impl Puzzle for Day12 {
+    const DAY: u8 = 12;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const LABELS: [&'static str; 2] = [
+        "1: steps in shortest path",
+        "2: steps in shortest possible path",
+    ];
+
+    type Input = HeightMap;
+    type Params = Params;
+    type Answer1 = Solution;
+    type Answer2 = Solution;
+    type Error = Error;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_str(input)
+    }
+
+    fn part1(input: &Self::Input, params: &Params) -> Result<Solution, Error> {
+        part1_with(input, params)
+    }
+
+    fn part2(input: &Self::Input, params: &Params) -> Result<Solution, Error> {
+        part2_with(input, params)
+    }
+
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn render(input: &Self::Input) -> String {
+        input.to_string()
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -102,4 +186,38 @@ pub enum Error {
     NoSolution,
     #[error("reading height map")]
     ElevationParse(#[from] height_map::Error),
+    #[error("visualizing")]
+    Visualize(#[from] common::visualize::Error),
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::ElevationParse(err) => err.diagnostic_mut(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::Answer;
+
+    #[test]
+    fn example_part1() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part1(&input).unwrap().answer, Answer::from(31));
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part2(&input).unwrap().answer, Answer::from(29));
+    }
+
+    #[test]
+    fn render_round_trips() {
+        common::puzzle::assert_round_trips::<Day12>(0..8, 20);
+    }
 }
@@ -9,7 +9,7 @@ pub struct PathNode {
 }
 
 impl PathNode {
-    pub fn iter(&self) -> Iter {
+    pub fn iter(&self) -> Iter<'_> {
         let mut nodes = Vec::new();
         nodes.push(self);
 
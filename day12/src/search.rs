@@ -0,0 +1,115 @@
+//! Draw the breadth-first search as it spreads across the map.
+
+use std::{
+    borrow::Cow,
+    collections::{HashSet, VecDeque},
+    fmt,
+    rc::Rc,
+};
+
+use aoclib::geometry::{
+    tile::{DisplayWidth, ToRgb},
+    Map, Point,
+};
+use common::visualize::Draw;
+
+use crate::{path_node::PathNode, HeightMap};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Cell {
+    Height(u8),
+    Visited(u8),
+    Frontier,
+    Path,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell::Height(0)
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cell::Height(height) => write!(f, "{}", (b'a' + height) as char),
+            Cell::Visited(_) => write!(f, "."),
+            Cell::Frontier => write!(f, "?"),
+            Cell::Path => write!(f, "#"),
+        }
+    }
+}
+
+impl DisplayWidth for Cell {
+    const DISPLAY_WIDTH: usize = 1;
+}
+
+impl ToRgb for Cell {
+    fn to_rgb(&self) -> [u8; 3] {
+        // heights run from 0 to 25; spread them across most of the brightness range
+        let shade = |height: u8| 40 + 8 * height;
+        match *self {
+            Cell::Height(height) => [shade(height); 3],
+            Cell::Visited(height) => [0, shade(height) / 2, shade(height)],
+            Cell::Frontier => [255, 220, 0],
+            Cell::Path => [255, 40, 40],
+        }
+    }
+}
+
+/// The state of a search across a height map.
+pub struct Search<'a> {
+    map: &'a HeightMap,
+    pub visited: HashSet<Point>,
+    pub queue: VecDeque<Rc<PathNode>>,
+    /// The path which was found, once the search is done.
+    pub path: Option<Rc<PathNode>>,
+}
+
+impl<'a> Search<'a> {
+    pub fn new(map: &'a HeightMap, initial: Point) -> Self {
+        let mut queue = VecDeque::new();
+        queue.push_back(Rc::new(PathNode {
+            location: initial,
+            prev: None,
+        }));
+        Self {
+            map,
+            visited: HashSet::new(),
+            queue,
+            path: None,
+        }
+    }
+
+    fn cells(&self) -> Map<Cell> {
+        let mut cells = Map::<Cell>::new(self.map.width(), self.map.height());
+        for (location, &height) in self.map.iter() {
+            cells[location] = if self.visited.contains(&location) {
+                Cell::Visited(height)
+            } else {
+                Cell::Height(height)
+            };
+        }
+        for node in &self.queue {
+            cells[node.location] = Cell::Frontier;
+        }
+        for node in self.path.iter().flat_map(|path| path.iter()) {
+            cells[node.location] = Cell::Path;
+        }
+        cells
+    }
+}
+
+impl fmt::Display for Search<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.cells().fmt(f)
+    }
+}
+
+impl Draw for Search<'_> {
+    type Tile = Cell;
+
+    fn frame(&self) -> Cow<'_, Map<Cell>> {
+        Cow::Owned(self.cells())
+    }
+}
@@ -0,0 +1,31 @@
+use rand::Rng;
+
+fn list<R: Rng + ?Sized>(rng: &mut R, depth: u32, out: &mut String) {
+    out.push('[');
+    for idx in 0..rng.gen_range(0..=5) {
+        if idx > 0 {
+            out.push(',');
+        }
+        if depth > 0 && rng.gen_bool(0.3) {
+            list(rng, depth - 1, out);
+        } else {
+            out.push_str(&rng.gen_range(0..=10).to_string());
+        }
+    }
+    out.push(']');
+}
+
+/// Generate `size` pairs of packets, nested up to four lists deep.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+    let mut input = String::new();
+    for idx in 0..size {
+        if idx > 0 {
+            input.push('\n');
+        }
+        for _ in 0..2 {
+            list(rng, 3, &mut input);
+            input.push('\n');
+        }
+    }
+    input
+}
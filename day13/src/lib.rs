@@ -1,15 +1,40 @@
-use aoclib::input::parse_newline_sep;
+use common::{
+    diagnostic::{parse_file, Diagnosed},
+    input::{blocks, join_blocks},
+    puzzle::NoParams,
+    Diagnostic, Puzzle, Solution,
+};
 use derive_more::From;
-use std::{cmp::Ordering, path::Path, str::FromStr};
+use rand::Rng;
+use std::{cmp::Ordering, fmt, io::Read, path::Path, str::FromStr};
 
+mod generate;
 mod parse;
 
+pub use generate::generate;
+
+/// The worked example from the puzzle description.
+pub const EXAMPLE: &str = include_str!("../example.txt");
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 struct Integer(u32);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct List(Vec<Value>);
 
+impl fmt::Display for List {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (idx, value) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{value}")?;
+        }
+        write!(f, "]")
+    }
+}
+
 impl PartialOrd for List {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -43,6 +68,15 @@ enum Value {
     Int(Integer),
 }
 
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::List(list) => write!(f, "{list}"),
+            Value::Int(Integer(int)) => write!(f, "{int}"),
+        }
+    }
+}
+
 impl PartialOrd for Value {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -67,31 +101,70 @@ impl Ord for Value {
     }
 }
 
-struct Pair {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pair {
     left: List,
     right: List,
 }
 
 impl FromStr for Pair {
-    type Err = parse::ParseError;
+    type Err = Diagnostic;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines = s.lines().map(str::trim).collect::<Vec<_>>();
+        Pair::parse_in(s, s)
+    }
+}
+
+impl fmt::Display for Pair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.left)?;
+        writeln!(f, "{}", self.right)
+    }
+}
+
+impl Pair {
+    /// Parse the block of `input` holding a single pair.
+    fn parse_in(input: &str, block: &str) -> Result<Self, Diagnostic> {
+        let lines = block.lines().map(str::trim).collect::<Vec<_>>();
         if lines.len() < 2 {
-            return Err(parse::ParseError::TooShort);
+            let end = &block[block.len()..];
+            return Err(Diagnostic::new(input, end, parse::ParseError::TooShort));
         }
-        if lines[2..].iter().any(|line| !line.is_empty()) {
-            return Err(parse::ParseError::ExtraTokens);
+        if let Some(extra) = lines[2..].iter().find(|line| !line.is_empty()) {
+            return Err(Diagnostic::new(
+                input,
+                extra,
+                parse::ParseError::ExtraTokens,
+            ));
         }
+        let list =
+            |line| List::parse_line(line).map_err(|(err, at)| Diagnostic::new(input, at, err));
         Ok(Pair {
-            left: List::from_str(lines[0])?,
-            right: List::from_str(lines[1])?,
+            left: list(lines[0])?,
+            right: list(lines[1])?,
         })
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let ordered_pairs_sum: u32 = parse_newline_sep::<Pair>(input)?
+pub fn parse_str(input: &str) -> Result<Vec<Pair>, Error> {
+    Ok(blocks(input)
+        .map(|block| Pair::parse_in(input, block))
+        .collect::<Result<_, _>>()?)
+}
+
+pub fn from_reader(mut reader: impl Read) -> Result<Vec<Pair>, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_str(&input)
+}
+
+pub fn parse(input: &Path) -> Result<Vec<Pair>, Error> {
+    parse_file(input, parse_str)
+}
+
+pub fn part1(pairs: &[Pair]) -> Result<Solution, Error> {
+    let ordered_pairs_sum: u32 = pairs
+        .iter()
         .enumerate()
         .filter_map(|(idx, Pair { left, right })| {
             (left < right)
@@ -99,21 +172,17 @@ pub fn part1(input: &Path) -> Result<(), Error> {
                 .then_some(idx as u32 + 1)
         })
         .sum();
-    println!("sum of indices of ordered pairs: {ordered_pairs_sum}");
-    Ok(())
+    Ok(Solution::new(ordered_pairs_sum))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(pairs: &[Pair]) -> Result<Solution, Error> {
     let divider_two = List::from_str("[[2]]").unwrap();
     let divider_six = List::from_str("[[6]]").unwrap();
 
-    let input = std::fs::read_to_string(input)?;
-    let mut packets = input
-        .lines()
-        .map(str::trim)
-        .filter(|line| !line.is_empty())
-        .map(List::from_str)
-        .collect::<Result<Vec<_>, _>>()?;
+    let mut packets = pairs
+        .iter()
+        .flat_map(|pair| [pair.left.clone(), pair.right.clone()])
+        .collect::<Vec<_>>();
     packets.push(divider_two.clone());
     packets.push(divider_six.clone());
     packets.sort();
@@ -132,9 +201,50 @@ pub fn part2(input: &Path) -> Result<(), Error> {
         + 1;
 
     let decoder_key = two_idx * six_idx;
-    println!("decoder key: {decoder_key}");
+    Ok(Solution::new(decoder_key))
+}
+
+pub fn solve_part1(input: &Path) -> Result<Solution, Error> {
+    part1(&parse(input)?)
+}
 
-    Ok(())
+pub fn solve_part2(input: &Path) -> Result<Solution, Error> {
+    part2(&parse(input)?)
+}
+
+/// Day 13: Distress Signal
+pub struct Day13;
+
+impl Puzzle for Day13 {
+    const DAY: u8 = 13;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const LABELS: [&'static str; 2] = ["sum of indices of ordered pairs", "decoder key"];
+
+    type Input = Vec<Pair>;
+    type Params = NoParams;
+    type Answer1 = Solution;
+    type Answer2 = Solution;
+    type Error = Error;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_str(input)
+    }
+
+    fn part1(input: &Self::Input, _params: &NoParams) -> Result<Solution, Error> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input, _params: &NoParams) -> Result<Solution, Error> {
+        part2(input)
+    }
+
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn render(input: &Self::Input) -> String {
+        join_blocks(input)
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -144,5 +254,45 @@ pub enum Error {
     #[error("no solution found")]
     NoSolution,
     #[error("parsing input")]
-    Parse(#[from] parse::ParseError),
+    Parse(#[from] Diagnostic),
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::Parse(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::Answer;
+
+    #[test]
+    fn example_part1() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part1(&input).unwrap().answer, Answer::from(13));
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part2(&input).unwrap().answer, Answer::from(140));
+    }
+
+    #[test]
+    fn parse_error_is_located() {
+        let Err(Error::Parse(diagnostic)) = parse_str("[1,2]\n[3,x]\n") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 4));
+    }
+
+    #[test]
+    fn render_round_trips() {
+        common::puzzle::assert_round_trips::<Day13>(0..8, 20);
+    }
 }
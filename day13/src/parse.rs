@@ -12,30 +12,48 @@ pub enum ParseError {
     ParseInt(#[from] ParseIntError),
     #[error("extra tokens")]
     ExtraTokens,
+    #[error("lists nested more than {MAX_DEPTH} deep")]
+    TooDeep,
+}
+
+/// Lists may nest at most this deep. Parsing recurses once per level, so without a limit a long
+/// enough run of `[` would overflow the stack.
+pub const MAX_DEPTH: usize = 64;
+
+/// A parse error, and the part of the input at fault.
+pub(crate) type Located<'a> = (ParseError, &'a str);
+
+/// The first char of `s`, or `s` itself if it's empty.
+fn first_char(s: &str) -> &str {
+    s.chars().next().map_or(s, |c| &s[..c.len_utf8()])
 }
 
 impl Integer {
-    fn parse(s: &str) -> Result<(Self, &str), ParseError> {
+    fn parse(s: &str) -> Result<(Self, &str), Located<'_>> {
         if s.is_empty() {
-            return Err(ParseError::TooShort);
+            return Err((ParseError::TooShort, s));
         }
         let split_point = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
         if split_point == 0 {
-            return Err(ParseError::UnexpectedToken);
+            return Err((ParseError::UnexpectedToken, first_char(s)));
         }
         let (used, rest) = s.split_at(split_point);
-        let value = used.parse()?;
+        let value = used.parse().map_err(|err| (ParseError::from(err), used))?;
         Ok((Integer(value), rest))
     }
 }
 
 impl List {
-    fn parse(s: &str) -> Result<(Self, &str), ParseError> {
+    /// Parse a list which is nested `depth` lists deep.
+    fn parse(s: &str, depth: usize) -> Result<(Self, &str), Located<'_>> {
         if s.is_empty() {
-            return Err(ParseError::TooShort);
+            return Err((ParseError::TooShort, s));
+        }
+        if depth > MAX_DEPTH {
+            return Err((ParseError::TooDeep, first_char(s)));
         }
         let Some(mut s) = s.strip_prefix('[') else {
-            return Err(ParseError::UnexpectedToken);
+            return Err((ParseError::UnexpectedToken, first_char(s)));
         };
 
         let mut inner = Vec::new();
@@ -44,7 +62,7 @@ impl List {
         }
 
         loop {
-            let (value, rest) = Value::parse(s)?;
+            let (value, rest) = Value::parse(s, depth + 1)?;
             inner.push(value);
 
             if let Some(rest) = rest.strip_prefix(']') {
@@ -52,29 +70,34 @@ impl List {
             } else if let Some(rest) = rest.strip_prefix(',') {
                 s = rest;
             } else {
-                return Err(ParseError::UnexpectedToken);
+                return Err((ParseError::UnexpectedToken, first_char(rest)));
             }
         }
     }
+
+    /// Parse an entire line as a list.
+    pub(crate) fn parse_line(s: &str) -> Result<Self, Located<'_>> {
+        let (list, rest) = List::parse(s, 1)?;
+        if !rest.is_empty() {
+            return Err((ParseError::ExtraTokens, rest));
+        }
+        Ok(list)
+    }
 }
 
 impl FromStr for List {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (list, rest) = List::parse(s)?;
-        if !rest.is_empty() {
-            return Err(ParseError::ExtraTokens);
-        }
-        Ok(list)
+        List::parse_line(s).map_err(|(err, _)| err)
     }
 }
 
 impl Value {
-    fn parse(s: &str) -> Result<(Self, &str), ParseError> {
+    fn parse(s: &str, depth: usize) -> Result<(Self, &str), Located<'_>> {
         Integer::parse(s)
             .map(|(i, rest)| (i.into(), rest))
-            .or_else(|_| List::parse(s).map(|(l, rest)| (l.into(), rest)))
+            .or_else(|_| List::parse(s, depth).map(|(l, rest)| (l.into(), rest)))
     }
 }
 
@@ -104,4 +127,15 @@ mod tests {
         dbg!(input);
         List::from_str(input).unwrap();
     }
+
+    #[test]
+    fn limits_nesting() {
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        List::from_str(&nested(MAX_DEPTH)).unwrap();
+
+        let input = nested(1 << 20);
+        let (err, at) = List::parse_line(&input).unwrap_err();
+        assert!(matches!(err, ParseError::TooDeep), "{err:?}");
+        assert_eq!(at.as_ptr(), input[MAX_DEPTH..].as_ptr());
+    }
 }
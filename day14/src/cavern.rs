@@ -1,8 +1,9 @@
-use std::{fmt, path::Path};
+use std::{borrow::Cow, fmt, path::Path};
 
-use aoclib::{
-    geometry::{Map, Point},
-    parse,
+use aoclib::geometry::{Map, Point};
+use common::{
+    diagnostic::{lines, parse_file},
+    visualize::Draw,
 };
 
 use crate::{rock_path::RockPath, tile::Tile, Error, SAND_SOURCE};
@@ -32,13 +33,22 @@ fn find_boundaries(points: impl IntoIterator<Item = Point>) -> Option<(Point, Po
     min.zip(max)
 }
 
+#[derive(Clone, PartialEq, Eq)]
 pub struct Cavern {
     pub map: Map<Tile>,
+    /// The paths the rock was drawn from, as they appeared in the input.
+    pub paths: Vec<RockPath>,
 }
 
 impl Cavern {
     pub fn parse(input: &Path) -> Result<Self, Error> {
-        let paths = parse::<RockPath>(input)?.collect::<Vec<_>>();
+        parse_file(input, Self::parse_str)
+    }
+
+    pub fn parse_str(input: &str) -> Result<Self, Error> {
+        let paths = lines(input)
+            .map(|line| RockPath::parse_in(input, line))
+            .collect::<Result<Vec<_>, _>>()?;
         let (min, max) = find_boundaries(
             paths
                 .iter()
@@ -58,7 +68,7 @@ impl Cavern {
             (max.y - min.y) as usize + 1,
         );
 
-        for path in paths {
+        for path in &paths {
             for window in path.nodes.windows(2) {
                 let [from, to]: [Point; 2] = window
                     .try_into()
@@ -83,7 +93,7 @@ impl Cavern {
             }
         }
 
-        Ok(Self { map })
+        Ok(Self { map, paths })
     }
 
     // Add a floor at two plus the highest y coordinate
@@ -152,3 +162,11 @@ impl fmt::Display for Cavern {
         self.map.flip_vertical().fmt(f)
     }
 }
+
+impl Draw for Cavern {
+    type Tile = Tile;
+
+    fn frame(&self) -> Cow<'_, Map<Tile>> {
+        Cow::Borrowed(&self.map)
+    }
+}
@@ -0,0 +1,40 @@
+use crate::SAND_SOURCE;
+use rand::Rng;
+use std::fmt::Write;
+
+/// Generate `size` rock paths.
+///
+/// Every rock stays within the pyramid beneath the sand source (`|x - 500| < y`), so it also fits
+/// within the floor added by part 2.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+    let source_x = SAND_SOURCE.x;
+    let depth = 10 + size as i32;
+    let mut input = String::new();
+    for _ in 0..size {
+        let mut y = rng.gen_range(2..=depth);
+        let mut x = rng.gen_range(source_x - y + 1..source_x + y);
+        let mut nodes = vec![format!("{x},{y}")];
+        let horizontal_first = rng.gen_bool(0.5);
+        for segment in 0..rng.gen_range(1..=5) {
+            // segments alternate between horizontal and vertical
+            if (segment % 2 == 0) == horizontal_first {
+                let reach = y - 1;
+                let to = (x + rng.gen_range(-8..=8)).clamp(source_x - reach, source_x + reach);
+                if to == x {
+                    continue;
+                }
+                x = to;
+            } else {
+                let lowest = ((x - source_x).abs() + 1).max(2);
+                let to = (y + rng.gen_range(-8..=8)).clamp(lowest, depth);
+                if to == y {
+                    continue;
+                }
+                y = to;
+            }
+            nodes.push(format!("{x},{y}"));
+        }
+        writeln!(input, "{}", nodes.join(" -> ")).unwrap();
+    }
+    input
+}
@@ -1,49 +1,192 @@
 mod cavern;
-mod hooks;
+mod generate;
 mod rock_path;
 mod tile;
 
-use aoclib::geometry::{map::RenderError, Point};
-use cavern::Cavern;
-use std::path::Path;
+use aoclib::geometry::Point;
+pub use cavern::Cavern;
+use common::{
+    diagnostic::Diagnosed,
+    input::join_lines,
+    repl::{Interactive, Simulation},
+    visualize::Hooks,
+    Diagnostic, Puzzle, Solution, VisualizeArgs,
+};
+pub use generate::generate;
+use rand::Rng;
+use std::{fmt, io::Read, path::Path};
+use structopt::StructOpt;
+
+/// The worked example from the puzzle description.
+pub const EXAMPLE: &str = include_str!("../example.txt");
 
 pub const SAND_SOURCE: Point = Point::new(500, 0);
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let mut cavern = Cavern::parse(input)?;
+// Ways to watch the sand fall.
+#[derive(Debug, Default, Clone, PartialEq, Eq, StructOpt)]
+pub struct Params {
+    #[structopt(flatten)]
+    pub visualize: VisualizeArgs,
+}
+
+pub fn parse_str(input: &str) -> Result<Cavern, Error> {
+    Cavern::parse_str(input)
+}
+
+pub fn from_reader(mut reader: impl Read) -> Result<Cavern, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_str(&input)
+}
+
+pub fn parse(input: &Path) -> Result<Cavern, Error> {
+    Cavern::parse(input)
+}
+
+pub fn part1(cavern: &Cavern) -> Result<Solution, Error> {
+    part1_with(cavern, &Params::default())
+}
+
+pub fn part1_with(cavern: &Cavern, params: &Params) -> Result<Solution, Error> {
+    let mut cavern = cavern.clone();
 
-    hooks::pre(&cavern)?;
+    let mut hooks = Hooks::pre(&cavern, &params.visualize)?;
 
     let mut came_to_rest = 0;
     while cavern.drop_sand() {
         came_to_rest += 1;
-        hooks::trace(&cavern)?;
+        tracing::trace!(unit = came_to_rest, "sand came to rest");
+        hooks.trace(&cavern)?;
     }
 
-    hooks::post(&cavern)?;
-    println!("{came_to_rest} units of sand came to rest");
+    hooks.post(&cavern)?;
+
+    Ok(Solution::new(came_to_rest))
+}
 
-    Ok(())
+pub fn part2(cavern: &Cavern) -> Result<Solution, Error> {
+    part2_with(cavern, &Params::default())
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let mut cavern = Cavern::parse(input)?;
+pub fn part2_with(cavern: &Cavern, params: &Params) -> Result<Solution, Error> {
+    let mut cavern = cavern.clone();
     cavern.add_floor();
 
-    hooks::pre(&cavern)?;
+    let mut hooks = Hooks::pre(&cavern, &params.visualize)?;
 
     let mut dropped_units = 0;
     while !cavern.map[SAND_SOURCE].is_blocked() {
         let _came_to_rest = cavern.drop_sand();
         debug_assert!(_came_to_rest, "sand must have stopped");
         dropped_units += 1;
-        hooks::trace(&cavern)?;
+        tracing::trace!(unit = dropped_units, "sand came to rest");
+        hooks.trace(&cavern)?;
     }
 
-    hooks::post(&cavern)?;
-    println!("{dropped_units} units of sand came to rest");
+    hooks.post(&cavern)?;
+
+    Ok(Solution::new(dropped_units))
+}
 
-    Ok(())
+/// Sand pouring into a cavern, one unit at a time.
+#[derive(Clone)]
+pub struct Pour {
+    cavern: Cavern,
+    came_to_rest: usize,
+}
+
+impl Simulation for Pour {
+    type Error = Error;
+
+    /// Drop a unit of sand.
+    fn step(&mut self) -> Result<bool, Error> {
+        // without a floor, sand eventually falls into the abyss; with one, it eventually blocks
+        // the source
+        if self.cavern.map[SAND_SOURCE].is_blocked() || !self.cavern.drop_sand() {
+            return Ok(false);
+        }
+        self.came_to_rest += 1;
+        Ok(true)
+    }
+
+    fn probes(&self) -> Vec<(String, i64)> {
+        vec![("rested".into(), self.came_to_rest as i64)]
+    }
+}
+
+impl fmt::Display for Pour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.cavern.fmt(f)
+    }
+}
+
+pub fn solve_part1(input: &Path) -> Result<Solution, Error> {
+    part1(&parse(input)?)
+}
+
+pub fn solve_part2(input: &Path) -> Result<Solution, Error> {
+    part2(&parse(input)?)
+}
+
+pub fn solve_part1_with(input: &Path, params: &Params) -> Result<Solution, Error> {
+    part1_with(&parse(input)?, params)
+}
+
+pub fn solve_part2_with(input: &Path, params: &Params) -> Result<Solution, Error> {
+    part2_with(&parse(input)?, params)
+}
+
+/// Day 14: Regolith Reservoir
+pub struct Day14;
+
+impl Puzzle for Day14 {
+    const DAY: u8 = 14;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const LABELS: [&'static str; 2] = [
+        "units of sand at rest (pt. 1)",
+        "units of sand at rest (pt. 2)",
+    ];
+
+    type Input = Cavern;
+    type Params = Params;
+    type Answer1 = Solution;
+    type Answer2 = Solution;
+    type Error = Error;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_str(input)
+    }
+
+    fn part1(input: &Self::Input, params: &Params) -> Result<Solution, Error> {
+        part1_with(input, params)
+    }
+
+    fn part2(input: &Self::Input, params: &Params) -> Result<Solution, Error> {
+        part2_with(input, params)
+    }
+
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn render(input: &Self::Input) -> String {
+        join_lines(&input.paths)
+    }
+}
+
+impl Interactive for Day14 {
+    type Simulation = Pour;
+
+    fn simulate(input: &Cavern, part: u8) -> Pour {
+        let mut cavern = input.clone();
+        if part == 2 {
+            cavern.add_floor();
+        }
+        Pour {
+            cavern,
+            came_to_rest: 0,
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -54,8 +197,40 @@ pub enum Error {
     NoSolution,
     #[error("bad input: {0}")]
     BadInput(&'static str),
-    #[error("rendering image")]
-    Rendering(#[from] RenderError),
-    #[error("adding frame to animation")]
-    Animating(#[from] aoclib::geometry::map::EncodingError),
+    #[error("visualizing")]
+    Visualize(#[from] common::visualize::Error),
+    #[error("parsing input")]
+    Parse(#[from] Diagnostic),
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::Parse(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::Answer;
+
+    #[test]
+    fn example_part1() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part1(&input).unwrap().answer, Answer::from(24));
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part2(&input).unwrap().answer, Answer::from(93));
+    }
+
+    #[test]
+    fn render_round_trips() {
+        common::puzzle::assert_round_trips::<Day14>(0..8, 20);
+    }
 }
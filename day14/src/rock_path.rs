@@ -1,34 +1,70 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 use aoclib::geometry::Point;
+use common::Diagnostic;
 
 use crate::Error;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RockPath {
     pub nodes: Vec<Point>,
 }
 
+impl fmt::Display for RockPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, node) in self.nodes.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " -> ")?;
+            }
+            write!(f, "{},{}", node.x, node.y)?;
+        }
+        Ok(())
+    }
+}
+
 impl FromStr for RockPath {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let nodes = s
-            .split("->")
-            .map(|point_s| {
-                let Some((left, right)) = point_s.split_once(',') else {
-                return Err(Error::BadInput("failed to split by comma"));
+        Ok(Self::parse_in(s, s)?)
+    }
+}
+
+impl RockPath {
+    /// Parse a single line of `input`.
+    pub fn parse_in(input: &str, line: &str) -> Result<Self, Diagnostic> {
+        let mut nodes = Vec::new();
+        let mut previous: Option<(Point, &str)> = None;
+
+        for point_s in line.split("->").map(str::trim) {
+            let Some((left, right)) = point_s.split_once(',') else {
+                return Err(Diagnostic::new(input, point_s, "failed to split by comma"));
+            };
+            let parse_coordinate = |coordinate: &str, name: &str| {
+                let coordinate = coordinate.trim();
+                coordinate.parse().map_err(|err| {
+                    Diagnostic::new(input, coordinate, format!("parsing {name}: {err}"))
+                })
             };
-                let left = left
-                    .trim()
-                    .parse()
-                    .map_err(|_| Error::BadInput("parsing left"))?;
-                let right = right
-                    .trim()
-                    .parse()
-                    .map_err(|_| Error::BadInput("parsing left"))?;
-                Ok(Point::new(left, right))
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+            let point = Point::new(parse_coordinate(left, "x")?, parse_coordinate(right, "y")?);
+
+            if let Some((from, from_s)) = previous {
+                if !(from.x == point.x || from.y == point.y) {
+                    // point at the whole segment
+                    let start = from_s.as_ptr() as usize - line.as_ptr() as usize;
+                    let end = point_s.as_ptr() as usize - line.as_ptr() as usize + point_s.len();
+                    return Err(Diagnostic::new(
+                        input,
+                        &line[start..end],
+                        "path segment was not orthogonal",
+                    ));
+                }
+            }
+
+            nodes.push(point);
+            previous = Some((point, point_s));
+        }
+
         Ok(Self { nodes })
     }
 }
@@ -0,0 +1,119 @@
+//! Draw the region the sensors cover, scaled down to fit in an image.
+
+use aoclib::geometry::{
+    tile::{DisplayWidth, ToRgb},
+    Map, Point,
+};
+use common::visualize::Draw;
+use std::{borrow::Cow, fmt};
+
+use crate::Report;
+
+/// The most cells along each side of the drawing.
+const MAX_SIDE: i32 = 400;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    #[default]
+    Unknown,
+    Covered,
+    Sensor,
+    Beacon,
+    Distress,
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            Cell::Unknown => '.',
+            Cell::Covered => '#',
+            Cell::Sensor => 'S',
+            Cell::Beacon => 'B',
+            Cell::Distress => '!',
+        };
+        write!(f, "{c}")
+    }
+}
+
+impl DisplayWidth for Cell {
+    const DISPLAY_WIDTH: usize = 1;
+}
+
+impl ToRgb for Cell {
+    fn to_rgb(&self) -> [u8; 3] {
+        match self {
+            Cell::Unknown => [0, 0, 0],
+            Cell::Covered => [40, 60, 120],
+            Cell::Sensor => [80, 220, 80],
+            Cell::Beacon => [220, 220, 80],
+            Cell::Distress => [255, 40, 40],
+        }
+    }
+}
+
+/// The part of `0..=upper_bound` in each dimension which the sensors added so far cover.
+///
+/// Each cell stands for a square of `scale * scale` positions, and counts as covered when the
+/// position at its center is.
+pub struct Coverage {
+    map: Map<Cell>,
+    scale: i32,
+}
+
+impl Coverage {
+    pub fn new(upper_bound: i32) -> Self {
+        let positions = upper_bound.max(0) + 1;
+        let scale = (positions + MAX_SIDE - 1) / MAX_SIDE;
+        let side = ((positions + scale - 1) / scale) as usize;
+        Self {
+            map: Map::new(side, side),
+            scale,
+        }
+    }
+
+    /// The cell containing `position`, if it's in bounds.
+    fn cell(&self, position: Point) -> Option<Point> {
+        let cell = Point::new(
+            position.x.div_euclid(self.scale),
+            position.y.div_euclid(self.scale),
+        );
+        self.map.in_bounds(cell).then_some(cell)
+    }
+
+    pub fn add(&mut self, report: &Report) {
+        let distance = (report.beacon - report.sensor).manhattan();
+        let half = self.scale / 2;
+        for cell in self.map.points() {
+            let center = Point::new(cell.x * self.scale + half, cell.y * self.scale + half);
+            if self.map[cell] == Cell::Unknown && (center - report.sensor).manhattan() <= distance {
+                self.map[cell] = Cell::Covered;
+            }
+        }
+
+        for (position, tile) in [(report.sensor, Cell::Sensor), (report.beacon, Cell::Beacon)] {
+            if let Some(cell) = self.cell(position) {
+                self.map[cell] = tile;
+            }
+        }
+    }
+
+    pub fn mark_distress(&mut self, position: Point) {
+        if let Some(cell) = self.cell(position) {
+            self.map[cell] = Cell::Distress;
+        }
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.map.flip_vertical().fmt(f)
+    }
+}
+
+impl Draw for Coverage {
+    type Tile = Cell;
+
+    fn frame(&self) -> Cow<'_, Map<Cell>> {
+        Cow::Borrowed(&self.map)
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{Report, UPPER_BOUND};
+use aoclib::geometry::Point;
+use rand::{seq::SliceRandom, Rng};
+use std::fmt::Write;
+
+/// A random point exactly `distance` from `center`.
+fn point_at<R: Rng + ?Sized>(rng: &mut R, center: Point, distance: i32) -> Point {
+    let dx = rng.gen_range(-distance..=distance);
+    let dy = distance - dx.abs();
+    let dy = if rng.gen_bool(0.5) { dy } else { -dy };
+    center + Point::new(dx, dy)
+}
+
+/// Generate reports from `size` sensors, plus four more.
+///
+/// The four extra sensors sit diagonally around the distress beacon, each reaching to within one
+/// of it; between them they cover the entire search region except the beacon itself. The other
+/// sensors are scattered at random, never reaching the beacon.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+    let distress = Point::new(
+        rng.gen_range(0..=UPPER_BOUND),
+        rng.gen_range(0..=UPPER_BOUND),
+    );
+
+    // any offset over half the region's width makes the diamonds span it
+    let offset = rng.gen_range(UPPER_BOUND / 2 + 1..=UPPER_BOUND);
+    let mut reports = [(-1, -1), (1, -1), (-1, 1), (1, 1)]
+        .into_iter()
+        .map(|(sx, sy)| {
+            let sensor = distress + Point::new(sx * offset, sy * offset);
+            Report {
+                sensor,
+                beacon: point_at(rng, sensor, 2 * offset - 1),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    while reports.len() < size + 4 {
+        let sensor = Point::new(
+            rng.gen_range(0..=UPPER_BOUND),
+            rng.gen_range(0..=UPPER_BOUND),
+        );
+        let distance = (distress - sensor).manhattan();
+        if distance < 2 {
+            continue;
+        }
+        let reach = rng.gen_range(1..distance);
+        reports.push(Report {
+            sensor,
+            beacon: point_at(rng, sensor, reach),
+        });
+    }
+    reports.shuffle(rng);
+
+    let mut input = String::new();
+    for report in &reports {
+        writeln!(input, "{report}").unwrap();
+    }
+    input
+}
@@ -1,15 +1,30 @@
+mod coverage;
+mod generate;
 mod range;
 
-use aoclib::{geometry::Point, parse};
+use aoclib::geometry::Point;
+use common::{
+    diagnostic::{parse_file, parse_lines, Diagnosed},
+    input::join_lines,
+    visualize::Hooks,
+    Diagnostic, Puzzle, Solution, VisualizeArgs,
+};
+use coverage::Coverage;
 use parse_display::{Display, FromStr};
-use std::{collections::HashSet, ops::RangeInclusive, path::Path};
+use rand::Rng;
+use std::{collections::HashSet, io::Read, ops::RangeInclusive, path::Path};
+use structopt::StructOpt;
 
 use crate::range::{contained_points, find_excluded, merge_ranges};
+pub use generate::generate;
 
-#[derive(Default, Clone, Copy, Display, FromStr)]
+/// The worked example from the puzzle description.
+pub const EXAMPLE: &str = include_str!("../example.txt");
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Display, FromStr)]
 #[display("Sensor at x={sensor.x}, y={sensor.y}: closest beacon is at x={beacon.x}, y={beacon.y}")]
 #[from_str(default)]
-struct Report {
+pub struct Report {
     sensor: Point,
     beacon: Point,
 }
@@ -38,14 +53,56 @@ impl Report {
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    const ROW: i32 = 2_000_000;
-    let row = std::env::var("ROW")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(ROW);
+pub fn parse_str(input: &str) -> Result<Vec<Report>, Error> {
+    Ok(parse_lines(input)?)
+}
+
+pub fn from_reader(mut reader: impl Read) -> Result<Vec<Report>, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_str(&input)
+}
+
+pub fn parse(input: &Path) -> Result<Vec<Report>, Error> {
+    parse_file(input, parse_str)
+}
+
+pub const ROW: i32 = 2_000_000;
+pub const UPPER_BOUND: i32 = 4_000_000;
+
+// Which part of the cave to search.
+//
+// The defaults are the values given for the real input; the example uses a much smaller region.
+#[derive(Debug, Clone, PartialEq, Eq, StructOpt)]
+pub struct Params {
+    /// row in which to count the positions where a beacon cannot be (part 1)
+    #[structopt(long, default_value = "2000000", allow_hyphen_values = true)]
+    pub row: i32,
+
+    /// largest x and y coordinate at which the distress beacon can be (part 2)
+    #[structopt(long, default_value = "4000000")]
+    pub upper_bound: i32,
+
+    #[structopt(flatten)]
+    pub visualize: VisualizeArgs,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            row: ROW,
+            upper_bound: UPPER_BOUND,
+            visualize: VisualizeArgs::default(),
+        }
+    }
+}
+
+pub fn part1(reports: &[Report]) -> Result<Solution, Error> {
+    part1_with(reports, &Params::default())
+}
 
-    let reports = parse::<Report>(input)?.collect::<Vec<_>>();
+pub fn part1_with(reports: &[Report], params: &Params) -> Result<Solution, Error> {
+    let row = params.row;
 
     let range_count: u64 = merge_ranges(
         reports
@@ -70,22 +127,19 @@ pub fn part1(input: &Path) -> Result<(), Error> {
 
     let impossible_count = range_count - sensor_count - beacon_count;
 
-    println!("{impossible_count} impossible positions at y={row}");
-    println!("  (range {range_count} - {sensor_count} sensors - {beacon_count} beacons)");
-    Ok(())
+    Ok(Solution::new(impossible_count)
+        .with_extra(format!("at y={row}"))
+        .with_extra(format!(
+            "range {range_count} - {sensor_count} sensors - {beacon_count} beacons"
+        )))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    const UPPER_BOUND: i32 = 4_000_000;
-
-    let upper_bound = std::env::var("UPPER_BOUND")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(UPPER_BOUND);
-
-    let bounds = 0..=upper_bound;
+pub fn part2(reports: &[Report]) -> Result<Solution, Error> {
+    part2_with(reports, &Params::default())
+}
 
-    let reports = parse::<Report>(input)?.collect::<Vec<_>>();
+pub fn part2_with(reports: &[Report], params: &Params) -> Result<Solution, Error> {
+    let bounds = 0..=params.upper_bound;
 
     let find_xy = |row| {
         let excluded = merge_ranges(
@@ -108,26 +162,136 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     #[cfg(not(feature = "parallelism"))]
     let (x, y) = bounds.clone().find_map(find_xy).ok_or(Error::NoSolution)?;
 
+    if params.visualize.is_enabled() {
+        visualize_part2(reports, params, Point::new(x as i32, y as i32))?;
+    }
+
     let tuning_frequency = x * 4000000 + y;
-    println!("tuning frequency: {tuning_frequency}");
-    println!("  at ({x}, {y})");
+    Ok(Solution::new(tuning_frequency).with_extra(format!("at ({x}, {y})")))
+}
 
+/// Draw the sensors' coverage growing one sensor at a time, then the distress beacon in the gap.
+///
+/// The search itself works a row at a time, so there's nothing useful to draw while it runs.
+fn visualize_part2(reports: &[Report], params: &Params, distress: Point) -> Result<(), Error> {
+    let mut coverage = Coverage::new(params.upper_bound);
+    let mut hooks = Hooks::pre(&coverage, &params.visualize)?;
+    for report in reports {
+        coverage.add(report);
+        hooks.trace(&coverage)?;
+    }
+    coverage.mark_distress(distress);
+    hooks.post(&coverage)?;
     Ok(())
 }
 
+pub fn solve_part1(input: &Path) -> Result<Solution, Error> {
+    part1(&parse(input)?)
+}
+
+pub fn solve_part2(input: &Path) -> Result<Solution, Error> {
+    part2(&parse(input)?)
+}
+
+pub fn solve_part1_with(input: &Path, params: &Params) -> Result<Solution, Error> {
+    part1_with(&parse(input)?, params)
+}
+
+pub fn solve_part2_with(input: &Path, params: &Params) -> Result<Solution, Error> {
+    part2_with(&parse(input)?, params)
+}
+
+/// Day 15: Beacon Exclusion Zone
+pub struct Day15;
+
+impl Puzzle for Day15 {
+    const DAY: u8 = 15;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const LABELS: [&'static str; 2] = ["impossible positions", "tuning frequency"];
+
+    type Input = Vec<Report>;
+    type Params = Params;
+    type Answer1 = Solution;
+    type Answer2 = Solution;
+    type Error = Error;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_str(input)
+    }
+
+    fn part1(input: &Self::Input, params: &Params) -> Result<Solution, Error> {
+        part1_with(input, params)
+    }
+
+    fn part2(input: &Self::Input, params: &Params) -> Result<Solution, Error> {
+        part2_with(input, params)
+    }
+
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn render(input: &Self::Input) -> String {
+        join_lines(input)
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error("no solution found")]
     NoSolution,
+    #[error("visualizing")]
+    Visualize(#[from] common::visualize::Error),
+    #[error("parsing input")]
+    Parse(#[from] Diagnostic),
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::Parse(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::Answer;
+
+    /// The example asks about a much smaller region than the real input.
+    fn example_params() -> Params {
+        Params {
+            row: 10,
+            upper_bound: 20,
+            ..Params::default()
+        }
+    }
+
+    #[test]
+    fn example_part1() {
+        let input = parse_str(EXAMPLE).unwrap();
+        let solution = part1_with(&input, &example_params()).unwrap();
+        assert_eq!(solution.answer, Answer::from(26));
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = parse_str(EXAMPLE).unwrap();
+        let solution = part2_with(&input, &example_params()).unwrap();
+        assert_eq!(solution.answer, Answer::from(56000011));
+    }
+
+    #[test]
+    fn cli_defaults_match_constants() {
+        assert_eq!(Params::from_iter(["day15"]), Params::default());
+    }
 
     #[test]
+    #[allow(clippy::manual_saturating_arithmetic)]
     fn single_report() {
         let report = Report {
             sensor: Point::new(8, 7),
@@ -157,4 +321,9 @@ mod tests {
             assert_eq!(contained_points as usize, expect);
         }
     }
+
+    #[test]
+    fn render_round_trips() {
+        common::puzzle::assert_round_trips::<Day15>(0..8, 20);
+    }
 }
@@ -114,7 +114,7 @@ where
 {
     // it is possible that inserting a range at position 1 has caused a new overlap with position 0,
     // so we need to check from one step lower.
-    let mut idx = idx.checked_sub(1).unwrap_or_default();
+    let mut idx = idx.saturating_sub(1);
     let Some(mut high_bound) = ranges.get(idx).map(high) else {
         // ranges is empty
         return;
@@ -204,13 +204,16 @@ where
             found.push(cursor);
             cursor = high(range);
         } else {
-            eprintln!("found an empty range: {cursor}..{}", low(range));
+            tracing::debug!(%cursor, next = %low(range), "found an empty range");
             return None;
         }
     }
 
     if found.len() > 1 {
-        eprintln!("found several points outside the ranges");
+        tracing::debug!(
+            count = found.len(),
+            "found several points outside the ranges"
+        );
     }
     (found.len() == 1).then(|| found[0])
 }
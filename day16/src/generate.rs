@@ -0,0 +1,63 @@
+use rand::{seq::SliceRandom, Rng};
+use std::{collections::BTreeSet, fmt::Write};
+
+/// Generate a connected network of `size` valves, or at least two and at most 676.
+///
+/// About a quarter of the valves have a nonzero flow rate; valve `AA`, where you start, never
+/// does.
+pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+    let n_valves = size.clamp(2, 26 * 26);
+    let mut names = (b'A'..=b'Z')
+        .flat_map(|first| (b'A'..=b'Z').map(move |second| [first, second]))
+        .skip(1)
+        .collect::<Vec<_>>();
+    names.shuffle(rng);
+    names.truncate(n_valves - 1);
+    names.insert(0, *b"AA");
+
+    // a random spanning tree keeps the network connected; a few extra tunnels add loops
+    let mut tunnels = vec![BTreeSet::new(); n_valves];
+    let mut connect = |a: usize, b: usize| {
+        if a != b {
+            tunnels[a].insert(b);
+            tunnels[b].insert(a);
+        }
+    };
+    for idx in 1..n_valves {
+        connect(idx, rng.gen_range(0..idx));
+    }
+    for _ in 0..n_valves / 4 {
+        connect(rng.gen_range(0..n_valves), rng.gen_range(0..n_valves));
+    }
+
+    let mut order = (0..n_valves).collect::<Vec<_>>();
+    order.shuffle(rng);
+
+    let name = |idx: usize| String::from_utf8_lossy(&names[idx]).into_owned();
+    let mut input = String::new();
+    for idx in order {
+        let flow_rate = if idx != 0 && rng.gen_bool(0.25) {
+            rng.gen_range(1..=25)
+        } else {
+            0
+        };
+        let mut downstream = tunnels[idx]
+            .iter()
+            .map(|&other| name(other))
+            .collect::<Vec<_>>();
+        downstream.shuffle(rng);
+        let tunnels = if downstream.len() == 1 {
+            "tunnel leads to valve"
+        } else {
+            "tunnels lead to valves"
+        };
+        writeln!(
+            input,
+            "Valve {} has flow rate={flow_rate}; {tunnels} {}",
+            name(idx),
+            downstream.join(", ")
+        )
+        .unwrap();
+    }
+    input
+}
@@ -1,28 +1,112 @@
-use aoclib::parse;
-use parse_display::{Display, FromStr};
-use std::path::Path;
+use common::{
+    diagnostic::{parse_file, parse_lines, Diagnosed},
+    input::join_lines,
+    puzzle::NoParams,
+    Diagnostic, Puzzle, Solution,
+};
+use parse_display::FromStr;
+use rand::Rng;
+use std::{fmt, io::Read, path::Path};
 
-const START: &'static str = "AA";
+mod generate;
 
-#[derive(Debug, FromStr, Display)]
-#[display("Valve {name} has flow rate={flow_rate}; tunnels lead to valves {downstream}")]
+pub use generate::generate;
+
+/// The worked example from the puzzle description.
+pub const EXAMPLE: &str = include_str!("../example.txt");
+
+// the start valve will be needed once the solution is implemented
+#[allow(dead_code)]
+const START: &str = "AA";
+
+#[derive(Debug, PartialEq, Eq, FromStr)]
 #[from_str(
     regex = r"^Valve (?P<name>\w+) has flow rate=(?P<flow_rate>\d+); tunnels? leads? to valves? (?P<downstream>[\w, ]+)$"
 )]
-struct ValveReport {
+pub struct ValveReport {
     name: String,
     flow_rate: u32,
     downstream: String,
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let n_valves = parse::<ValveReport>(input)?.count();
-    println!("parsed {n_valves} valves");
-    Ok(())
+impl fmt::Display for ValveReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tunnels = if self.downstream.contains(',') {
+            "tunnels lead to valves"
+        } else {
+            "tunnel leads to valve"
+        };
+        write!(
+            f,
+            "Valve {} has flow rate={}; {tunnels} {}",
+            self.name, self.flow_rate, self.downstream
+        )
+    }
+}
+
+pub fn parse_str(input: &str) -> Result<Vec<ValveReport>, Error> {
+    Ok(parse_lines(input)?)
+}
+
+pub fn from_reader(mut reader: impl Read) -> Result<Vec<ValveReport>, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_str(&input)
+}
+
+pub fn parse(input: &Path) -> Result<Vec<ValveReport>, Error> {
+    parse_file(input, parse_str)
+}
+
+pub fn part1(_valves: &[ValveReport]) -> Result<Solution, Error> {
+    Err(Error::Unimplemented)
+}
+
+pub fn part2(valves: &[ValveReport]) -> Result<Solution, Error> {
+    unimplemented!("{} valves", valves.len())
+}
+
+pub fn solve_part1(input: &Path) -> Result<Solution, Error> {
+    part1(&parse(input)?)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    unimplemented!("input file: {:?}", input)
+pub fn solve_part2(input: &Path) -> Result<Solution, Error> {
+    part2(&parse(input)?)
+}
+
+/// Day 16: Proboscidea Volcanium
+pub struct Day16;
+
+impl Puzzle for Day16 {
+    const DAY: u8 = 16;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const LABELS: [&'static str; 2] = ["pressure released", "pressure released with an elephant"];
+
+    type Input = Vec<ValveReport>;
+    type Params = NoParams;
+    type Answer1 = Solution;
+    type Answer2 = Solution;
+    type Error = Error;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse_str(input)
+    }
+
+    fn part1(input: &Self::Input, _params: &NoParams) -> Result<Solution, Error> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input, _params: &NoParams) -> Result<Solution, Error> {
+        part2(input)
+    }
+
+    fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn render(input: &Self::Input) -> String {
+        join_lines(input)
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -31,4 +115,42 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("no solution found")]
     NoSolution,
+    #[error("not yet implemented")]
+    Unimplemented,
+    #[error("parsing input")]
+    Parse(#[from] Diagnostic),
+}
+
+impl Diagnosed for Error {
+    fn diagnostic_mut(&mut self) -> Option<&mut Diagnostic> {
+        match self {
+            Error::Parse(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::Answer;
+
+    #[test]
+    #[ignore = "part 1 is not yet implemented"]
+    fn example_part1() {
+        let input = parse_str(EXAMPLE).unwrap();
+        assert_eq!(part1(&input).unwrap().answer, Answer::from(1651));
+    }
+
+    #[test]
+    #[ignore = "part 2 is not yet implemented"]
+    fn example_part2() {
+        let input = parse_str(EXAMPLE).unwrap();
+        part2(&input).unwrap();
+    }
+
+    #[test]
+    fn render_round_trips() {
+        common::puzzle::assert_round_trips::<Day16>(0..8, 20);
+    }
 }